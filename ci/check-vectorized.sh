@@ -0,0 +1,24 @@
+#!/bin/sh
+# Check that the loops in examples/vectorized.rs compile to code without
+# calls (to the system math library), which would stop them from being
+# vectorized, and, for entries `function:instruction`, that the packed
+# instruction is used.
+set -e
+cargo rustc --release --features vecmath --example vectorized -- --emit asm
+asm=$(ls -t target/release/examples/vectorized-*.s | head -n 1)
+for check in exp_in_place div_approx_in_place:rcpps; do
+    f=${check%%:*}
+    body=$(sed -n "/^$f:/,/\.cfi_endproc/p" "$asm")
+    if [ -z "$body" ]; then
+        echo "$f not found in $asm"
+        exit 1
+    fi
+    if echo "$body" | grep -E "\bcall"; then
+        echo "$f has calls"
+        exit 1
+    fi
+    if [ "$check" != "$f" ] && ! echo "$body" | grep -q "${check#*:}"; then
+        echo "$f is not vectorized"
+        exit 1
+    fi
+done
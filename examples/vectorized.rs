@@ -0,0 +1,32 @@
+//! Loops that the compiler should vectorize with the `vecmath` feature.
+//!
+//! `ci/check-vectorized.sh` builds this example and checks that the
+//! assembly of each of these functions has no calls.
+
+extern crate fast_floats;
+
+use std::hint::black_box;
+
+use fast_floats::Fast;
+
+#[no_mangle]
+#[inline(never)]
+pub fn exp_in_place(xs: &mut [Fast<f64>]) {
+    for x in xs {
+        *x = x.exp();
+    }
+}
+
+#[no_mangle]
+#[inline(never)]
+pub fn div_approx_in_place(xs: &mut [Fast<f32>], ys: &[Fast<f32>]) {
+    fast_floats::recip::div_in_place_approx(xs, ys);
+}
+
+fn main() {
+    let mut xs = [Fast(1.); 64];
+    exp_in_place(black_box(&mut xs));
+    let mut zs = [Fast(1f32); 64];
+    div_approx_in_place(black_box(&mut zs), black_box(&[Fast(3.); 64]));
+    println!("{:?} {:?}", xs[0], zs[0]);
+}
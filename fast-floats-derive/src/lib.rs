@@ -0,0 +1,156 @@
+//! Derive macros for [fast-floats](https://docs.rs/fast-floats).
+//!
+//! Use them through the `derive` feature of fast-floats, which re-exports
+//! them.
+
+use proc_macro::TokenStream;
+use proc_macro2::TokenStream as TokenStream2;
+use quote::{format_ident, quote};
+use syn::{parse_macro_input, Data, DeriveInput, Error, Fields, Index, Member, Type};
+
+/// Derive element-wise arithmetic for a struct of fast floats.
+///
+/// For a struct whose fields are all `Fast` floats, or other types with the
+/// same operators (such as other `FastOps` structs), this implements:
+///
+/// - `Add`, `Sub`, `Mul` and `Div` of two structs, field by field, and the
+///   corresponding assignment operators;
+/// - `Mul` and `Div` by a scalar, and their assignment operators;
+/// - `Neg`.
+///
+/// The scalar type is the type of the first field, unless given with the
+/// attribute `#[fast_ops(scalar = Type)]`. The struct must be `Copy` for the
+/// assignment operators.
+///
+/// ```ignore
+/// use fast_floats::{Fast, FastOps};
+///
+/// #[derive(Copy, Clone, Debug, PartialEq, FastOps)]
+/// struct State {
+///     position: Fast<f64>,
+///     velocity: Fast<f64>,
+/// }
+///
+/// let s = State { position: Fast(1.), velocity: Fast(2.) };
+/// let t = s + s * Fast(0.5);
+/// assert_eq!(t, State { position: Fast(1.5), velocity: Fast(3.) });
+/// ```
+#[proc_macro_derive(FastOps, attributes(fast_ops))]
+pub fn derive_fast_ops(input: TokenStream) -> TokenStream {
+    let input = parse_macro_input!(input as DeriveInput);
+    expand(input).unwrap_or_else(Error::into_compile_error).into()
+}
+
+fn expand(input: DeriveInput) -> syn::Result<TokenStream2> {
+    let fields = match &input.data {
+        Data::Struct(data) => &data.fields,
+        _ => return Err(Error::new_spanned(&input.ident, "FastOps can only be derived for structs")),
+    };
+    if fields.is_empty() {
+        return Err(Error::new_spanned(&input.ident, "FastOps needs a struct with fields"));
+    }
+    let members: Vec<Member> = match fields {
+        Fields::Named(named) => named.named.iter()
+            .map(|f| Member::Named(f.ident.clone().unwrap()))
+            .collect(),
+        _ => (0..fields.len()).map(|i| Member::Unnamed(Index::from(i))).collect(),
+    };
+    let types: Vec<&Type> = fields.iter().map(|f| &f.ty).collect();
+    let scalar = scalar_type(&input)?.unwrap_or_else(|| types[0].clone());
+
+    let name = &input.ident;
+    let (impl_generics, ty_generics, where_clause) = input.generics.split_for_impl();
+    let predicates = where_clause.map(|w| &w.predicates);
+    let mut out = TokenStream2::new();
+
+    for (op, method) in [("Add", "add"), ("Sub", "sub"), ("Mul", "mul"), ("Div", "div")] {
+        let trait_ = format_ident!("{}", op);
+        let method = format_ident!("{}", method);
+        let assign_trait = format_ident!("{}Assign", op);
+        let assign_method = format_ident!("{}_assign", method);
+        out.extend(quote! {
+            impl #impl_generics core::ops::#trait_ for #name #ty_generics
+                where #(#types: core::ops::#trait_<Output = #types>,)* #predicates
+            {
+                type Output = Self;
+                #[inline(always)]
+                fn #method(self, rhs: Self) -> Self {
+                    #name {
+                        #(#members: core::ops::#trait_::#method(self.#members, rhs.#members),)*
+                    }
+                }
+            }
+
+            impl #impl_generics core::ops::#assign_trait for #name #ty_generics
+                where Self: Copy, #(#types: core::ops::#trait_<Output = #types>,)* #predicates
+            {
+                #[inline(always)]
+                fn #assign_method(&mut self, rhs: Self) {
+                    *self = core::ops::#trait_::#method(*self, rhs);
+                }
+            }
+        });
+    }
+
+    for (op, method) in [("Mul", "mul"), ("Div", "div")] {
+        let trait_ = format_ident!("{}", op);
+        let method = format_ident!("{}", method);
+        let assign_trait = format_ident!("{}Assign", op);
+        let assign_method = format_ident!("{}_assign", method);
+        out.extend(quote! {
+            impl #impl_generics core::ops::#trait_<#scalar> for #name #ty_generics
+                where #scalar: Copy, #(#types: core::ops::#trait_<#scalar, Output = #types>,)*
+                      #predicates
+            {
+                type Output = Self;
+                #[inline(always)]
+                fn #method(self, rhs: #scalar) -> Self {
+                    #name {
+                        #(#members: core::ops::#trait_::#method(self.#members, rhs),)*
+                    }
+                }
+            }
+
+            impl #impl_generics core::ops::#assign_trait<#scalar> for #name #ty_generics
+                where Self: Copy, #scalar: Copy,
+                      #(#types: core::ops::#trait_<#scalar, Output = #types>,)* #predicates
+            {
+                #[inline(always)]
+                fn #assign_method(&mut self, rhs: #scalar) {
+                    *self = core::ops::#trait_::#method(*self, rhs);
+                }
+            }
+        });
+    }
+
+    out.extend(quote! {
+        impl #impl_generics core::ops::Neg for #name #ty_generics
+            where #(#types: core::ops::Neg<Output = #types>,)* #predicates
+        {
+            type Output = Self;
+            #[inline(always)]
+            fn neg(self) -> Self {
+                #name {
+                    #(#members: core::ops::Neg::neg(self.#members),)*
+                }
+            }
+        }
+    });
+    Ok(out)
+}
+
+/// Return the type given with `#[fast_ops(scalar = Type)]`, if any.
+fn scalar_type(input: &DeriveInput) -> syn::Result<Option<Type>> {
+    let mut scalar = None;
+    for attr in input.attrs.iter().filter(|a| a.path().is_ident("fast_ops")) {
+        attr.parse_nested_meta(|meta| {
+            if meta.path.is_ident("scalar") {
+                scalar = Some(meta.value()?.parse()?);
+                Ok(())
+            } else {
+                Err(meta.error("unknown fast_ops attribute, expected `scalar`"))
+            }
+        })?;
+    }
+    Ok(scalar)
+}
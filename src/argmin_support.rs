@@ -0,0 +1,138 @@
+//! Support for `Fast` as the float type of the argmin optimization solvers.
+//!
+//! With the num-traits impls in the crate root, `Fast<f32>` and `Fast<f64>`
+//! are `ArgminFloat`s. The argmin-math traits are implemented for `Fast` as a
+//! scalar parameter; collections like `Vec<Fast<f64>>` can not be given these
+//! (foreign) traits from this crate.
+
+use argmin::core::KvValue;
+use argmin_math::{
+    ArgminAdd,
+    ArgminSub,
+    ArgminMul,
+    ArgminDiv,
+    ArgminDot,
+    ArgminL1Norm,
+    ArgminL2Norm,
+    ArgminZero,
+    ArgminZeroLike,
+    ArgminConj,
+    ArgminMinMax,
+    ArgminTranspose,
+};
+
+use Fast;
+
+macro_rules! impl_argmin {
+    ($($f:ident)+) => {
+        $(
+        impl From<Fast<$f>> for KvValue {
+            #[inline]
+            fn from(x: Fast<$f>) -> KvValue { KvValue::Float(x.0 as f64) }
+        }
+
+        impl ArgminAdd<Fast<$f>, Fast<$f>> for Fast<$f> {
+            #[inline]
+            fn add(&self, other: &Self) -> Self { *self + *other }
+        }
+
+        impl ArgminSub<Fast<$f>, Fast<$f>> for Fast<$f> {
+            #[inline]
+            fn sub(&self, other: &Self) -> Self { *self - *other }
+        }
+
+        impl ArgminMul<Fast<$f>, Fast<$f>> for Fast<$f> {
+            #[inline]
+            fn mul(&self, other: &Self) -> Self { *self * *other }
+        }
+
+        impl ArgminDiv<Fast<$f>, Fast<$f>> for Fast<$f> {
+            #[inline]
+            fn div(&self, other: &Self) -> Self { *self / *other }
+        }
+
+        impl ArgminDot<Fast<$f>, Fast<$f>> for Fast<$f> {
+            #[inline]
+            fn dot(&self, other: &Self) -> Self { *self * *other }
+        }
+
+        impl ArgminL1Norm<Fast<$f>> for Fast<$f> {
+            #[inline]
+            fn l1_norm(&self) -> Self { self.abs() }
+        }
+
+        impl ArgminL2Norm<Fast<$f>> for Fast<$f> {
+            #[inline]
+            fn l2_norm(&self) -> Self { self.abs() }
+        }
+
+        impl ArgminZero for Fast<$f> {
+            #[inline]
+            fn zero() -> Self { Fast(0.) }
+        }
+
+        impl ArgminZeroLike for Fast<$f> {
+            #[inline]
+            fn zero_like(&self) -> Self { Fast(0.) }
+        }
+
+        impl ArgminConj for Fast<$f> {
+            #[inline]
+            fn conj(&self) -> Self { *self }
+        }
+
+        impl ArgminMinMax for Fast<$f> {
+            #[inline]
+            fn min(x: &Self, y: &Self) -> Self { x.min(*y) }
+
+            #[inline]
+            fn max(x: &Self, y: &Self) -> Self { x.max(*y) }
+        }
+
+        impl ArgminTranspose<Fast<$f>> for Fast<$f> {
+            #[inline]
+            fn t(self) -> Self { self }
+        }
+        )+
+    }
+}
+
+impl_argmin!(f32 f64);
+
+#[cfg(test)]
+mod tests {
+    extern crate alloc;
+
+    use self::alloc::vec::Vec;
+    use argmin::core::{CostFunction, Error, Executor};
+    use argmin::solver::brent::BrentOpt;
+    use argmin::solver::neldermead::NelderMead;
+
+    use Fast;
+
+    struct Parabola;
+
+    impl CostFunction for Parabola {
+        type Param = Fast<f64>;
+        type Output = Fast<f64>;
+
+        fn cost(&self, x: &Fast<f64>) -> Result<Fast<f64>, Error> {
+            Ok((*x - 3.) * (*x - 3.) + 1.)
+        }
+    }
+
+    #[test]
+    fn minimize() {
+        let solver = NelderMead::new(Vec::from([Fast(0.), Fast(1.)]))
+            .with_sd_tolerance(Fast(1e-10)).unwrap();
+        let res = Executor::new(Parabola, solver)
+            .configure(|state| state.max_iters(200))
+            .run().unwrap();
+        let x = res.state.best_param.unwrap();
+        assert!((x.get() - 3.).abs() < 1e-4);
+
+        let res = Executor::new(Parabola, BrentOpt::new(Fast(-10.), Fast(10.)))
+            .run().unwrap();
+        assert!((res.state.best_param.unwrap().get() - 3.).abs() < 1e-6);
+    }
+}
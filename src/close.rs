@@ -0,0 +1,187 @@
+//! Approximate comparison of fast floats, for tests.
+//!
+//! Fast-math results depend on how the compiler chose to reorder and fuse
+//! operations, so they should be compared with a tolerance rather than with
+//! `assert_eq!`. The [`assert_fast_close!`](../macro.assert_fast_close.html)
+//! macro does that for `Fast` values, slices and arrays of them.
+
+use std::fmt;
+
+use Fast;
+
+/// A tolerance for [`Close`](trait.Close.html) comparisons.
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub enum Tolerance {
+    /// Maximum relative difference, `|a - b| <= tol · max(|a|, |b|)`
+    Relative(f64),
+    /// Maximum distance in units in the last place: the number of floats
+    /// between `a` and `b`
+    Ulps(u64),
+}
+
+/// The first difference found by [`Close::mismatch`](trait.Close.html).
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub enum Mismatch {
+    /// The values at `index` (zero for scalars) are too far apart
+    Value {
+        index: usize,
+        left: f64,
+        right: f64,
+        /// Distance in units in the last place of the compared float type
+        ulps: u64,
+    },
+    /// The slices have different lengths
+    Length {
+        left: usize,
+        right: usize,
+    },
+}
+
+impl fmt::Display for Mismatch {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match *self {
+            Mismatch::Value { index, left, right, ulps } => {
+                let rel = (left - right).abs() / left.abs().max(right.abs());
+                write!(f, "at index {}\n  left: {:?}\n right: {:?}\n  relative difference {:e}, {} ulps",
+                       index, left, right, rel, ulps)
+            }
+            Mismatch::Length { left, right } => {
+                write!(f, "lengths differ\n  left: {}\n right: {}", left, right)
+            }
+        }
+    }
+}
+
+/// Values that can be compared with a [`Tolerance`](enum.Tolerance.html).
+pub trait Close {
+    /// Return the first difference between `self` and `other` that is not
+    /// within `tol`, or `None` if they are close.
+    ///
+    /// NaN is not close to anything.
+    fn mismatch(&self, other: &Self, tol: Tolerance) -> Option<Mismatch>;
+}
+
+macro_rules! impl_close {
+    ($($f:ident $i:ident)+) => {
+        $(
+        impl Close for Fast<$f> {
+            fn mismatch(&self, other: &Self, tol: Tolerance) -> Option<Mismatch> {
+                let (a, b) = (self.0, other.0);
+                // map the bits to integers that are ordered like the floats
+                let ordered = |x: $f| {
+                    let i = x.to_bits() as $i;
+                    if i < 0 { $i::MIN - i } else { i }
+                };
+                let ulps = if a.is_nan() || b.is_nan() {
+                    u64::MAX
+                } else {
+                    (ordered(a) as i64).abs_diff(ordered(b) as i64)
+                };
+                let close = a == b || match tol {
+                    Tolerance::Relative(rel) => {
+                        ((a - b).abs() as f64) <= rel * (a.abs().max(b.abs()) as f64)
+                    }
+                    Tolerance::Ulps(n) => ulps <= n,
+                };
+                if close {
+                    None
+                } else {
+                    Some(Mismatch::Value { index: 0, left: a as f64, right: b as f64, ulps })
+                }
+            }
+        }
+        )+
+    }
+}
+
+impl_close!(f32 i32 f64 i64);
+
+impl<T: Close> Close for [T] {
+    fn mismatch(&self, other: &Self, tol: Tolerance) -> Option<Mismatch> {
+        if self.len() != other.len() {
+            return Some(Mismatch::Length { left: self.len(), right: other.len() });
+        }
+        self.iter().zip(other).enumerate().find_map(|(i, (a, b))| {
+            match a.mismatch(b, tol)? {
+                Mismatch::Value { left, right, ulps, .. } => {
+                    Some(Mismatch::Value { index: i, left, right, ulps })
+                }
+                m => Some(m),
+            }
+        })
+    }
+}
+
+impl<T: Close, const N: usize> Close for [T; N] {
+    fn mismatch(&self, other: &Self, tol: Tolerance) -> Option<Mismatch> {
+        self[..].mismatch(&other[..], tol)
+    }
+}
+
+impl<T: Close + ?Sized> Close for &T {
+    fn mismatch(&self, other: &Self, tol: Tolerance) -> Option<Mismatch> {
+        (**self).mismatch(*other, tol)
+    }
+}
+
+#[doc(hidden)]
+#[track_caller]
+pub fn assert_close<T: Close + ?Sized>(left: &T, right: &T, tol: Tolerance) {
+    if let Some(m) = left.mismatch(right, tol) {
+        panic!("assertion `left ≈ right` failed ({:?}) {}", tol, m);
+    }
+}
+
+/// Assert that two `Fast` values, or slices or arrays of them, are equal
+/// within a relative or ULP tolerance.
+///
+/// On failure, the panic message shows the first element that differs, with
+/// the relative difference and the distance in units in the last place.
+///
+/// ```
+/// #[macro_use]
+/// extern crate fast_floats;
+/// use fast_floats::Fast;
+///
+/// # fn main() {
+/// let third = Fast(1f64) / Fast(3.);
+/// assert_fast_close!(third * Fast(3.), Fast(1.), ulps = 2);
+/// assert_fast_close!([third; 2], [Fast(0.3333333); 2], rel = 1e-6);
+/// # }
+/// ```
+///
+/// ```should_panic
+/// # #[macro_use] extern crate fast_floats;
+/// # use fast_floats::Fast;
+/// # fn main() {
+/// assert_fast_close!([Fast(1f32), Fast(2.)], [Fast(1.), Fast(2.1)], rel = 1e-3);
+/// # }
+/// ```
+#[macro_export]
+macro_rules! assert_fast_close {
+    ($left:expr, $right:expr, rel = $tol:expr $(,)?) => {
+        $crate::close::assert_close(&$left, &$right, $crate::close::Tolerance::Relative($tol))
+    };
+    ($left:expr, $right:expr, ulps = $tol:expr $(,)?) => {
+        $crate::close::assert_close(&$left, &$right, $crate::close::Tolerance::Ulps($tol))
+    };
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn close() {
+        assert_fast_close!(Fast(1f32), Fast(1. + f32::EPSILON), ulps = 1);
+        assert_fast_close!(Fast(-0f64), Fast(0.), ulps = 0);
+        assert_fast_close!(&[Fast(1e10f64)][..], &[Fast(1e10 + 1.)][..], rel = 1e-9);
+
+        let tol = Tolerance::Ulps(1);
+        assert!(Fast(1f64).mismatch(&Fast(f64::NAN), tol).is_some());
+        assert_eq!([Fast(1f32)][..].mismatch(&[Fast(1.), Fast(2.)], tol),
+                   Some(Mismatch::Length { left: 1, right: 2 }));
+        assert_eq!([Fast(1f32), Fast(2.)].mismatch(&[Fast(1.), Fast(-2.)], tol),
+                   Some(Mismatch::Value { index: 1, left: 2., right: -2., ulps: 2 * 0x4000_0000 }));
+    }
+}
@@ -0,0 +1,106 @@
+//! Color space transfer functions.
+//!
+//! Color components are in the range `0` to `1`.
+
+use FastFloat;
+use Fast;
+
+#[inline(always)]
+fn srgb_to_linear<F: FastFloat>(c: F) -> F {
+    if c <= F::from_f64(0.04045) {
+        c * F::from_f64(1. / 12.92)
+    } else {
+        ((c + F::from_f64(0.055)) * F::from_f64(1. / 1.055)).powf(F::from_f64(2.4))
+    }
+}
+
+#[inline(always)]
+fn linear_to_srgb<F: FastFloat>(l: F) -> F {
+    if l <= F::from_f64(0.0031308) {
+        l * F::from_f64(12.92)
+    } else {
+        F::from_f64(1.055) * l.powf(F::from_f64(1. / 2.4)) - F::from_f64(0.055)
+    }
+}
+
+macro_rules! impl_color {
+    ($($f:ident)+) => {
+        $(
+        impl Fast<$f> {
+            /// Convert an sRGB encoded color component to linear light.
+            #[inline]
+            pub fn srgb_to_linear(self) -> Self {
+                srgb_to_linear(self)
+            }
+
+            /// Convert a linear light color component to sRGB encoding.
+            #[inline]
+            pub fn linear_to_srgb(self) -> Self {
+                linear_to_srgb(self)
+            }
+
+            /// Apply the power law gamma curve `self^gamma`.
+            #[inline]
+            pub fn apply_gamma(self, gamma: Self) -> Self {
+                self.powf(gamma)
+            }
+        }
+        )+
+    }
+}
+
+impl_color!(f32 f64);
+
+/// Convert sRGB encoded color components to linear light in place, as with
+/// `Fast::srgb_to_linear`.
+///
+/// ```
+/// use fast_floats::Fast;
+/// use fast_floats::color::{srgb_to_linear_in_place, linear_to_srgb_in_place};
+///
+/// let mut rgb = [0., 0.5, 1.].map(Fast);
+/// srgb_to_linear_in_place(&mut rgb);
+/// assert!((rgb[1].get() - 0.2140f32).abs() < 1e-4);
+/// linear_to_srgb_in_place(&mut rgb);
+/// assert!((rgb[1].get() - 0.5f32).abs() < 1e-5);
+/// ```
+pub fn srgb_to_linear_in_place<F: FastFloat>(xs: &mut [F]) {
+    for x in xs {
+        *x = srgb_to_linear(*x);
+    }
+}
+
+/// Convert linear light color components to sRGB encoding in place, as with
+/// `Fast::linear_to_srgb`.
+pub fn linear_to_srgb_in_place<F: FastFloat>(xs: &mut [F]) {
+    for x in xs {
+        *x = linear_to_srgb(*x);
+    }
+}
+
+/// Apply the power law gamma curve `x^gamma` to each element in place.
+pub fn apply_gamma_in_place<F: FastFloat>(xs: &mut [F], gamma: F) {
+    for x in xs {
+        *x = x.powf(gamma);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn srgb_round_trip() {
+        for i in 0..=100 {
+            let c = Fast(i as f64 / 100.);
+            assert!((c.srgb_to_linear().linear_to_srgb() - c).abs() < Fast(1e-12));
+        }
+        assert_eq!(Fast(0f32).srgb_to_linear(), Fast(0.));
+        assert!((Fast(1f32).linear_to_srgb().get() - 1.).abs() < 1e-6);
+        assert!((Fast(0.5f64).apply_gamma(Fast(2.2)).get() - 0.2176376).abs() < 1e-6);
+
+        let mut xs = [0.25, 1.].map(Fast);
+        apply_gamma_in_place(&mut xs, Fast(0.5));
+        assert_eq!(xs, [0.5, 1.].map(Fast));
+    }
+}
@@ -0,0 +1,108 @@
+//! Feedback controllers.
+
+use FastFloat;
+
+/// A PID (proportional–integral–derivative) controller.
+///
+/// Each [`update`](#method.update) returns the control output
+///
+/// ```text
+/// kp · e + clamp(∫ ki · e dt, ±integral_limit) - kd · d(measurement)/dt
+/// ```
+///
+/// where `e = setpoint - measurement`. The integral term is clamped to stop
+/// it from winding up while the output saturates, and the derivative term
+/// uses the measurement rather than the error, so that steps in the
+/// setpoint don't cause spikes in the output.
+///
+/// ```
+/// use fast_floats::Fast;
+/// use fast_floats::control::Pid;
+///
+/// // drive a first order system toward 1
+/// let mut pid = Pid::new(Fast(2f32), Fast(1.), Fast(0.), Fast(10.));
+/// let mut y = Fast(0.);
+/// for _ in 0..1000 {
+///     let u = pid.update(Fast(1.), y, Fast(0.01));
+///     y += (u - y) * Fast(0.01);
+/// }
+/// assert!((y.get() - 1.).abs() < 1e-2);
+/// ```
+#[derive(Copy, Clone, Debug)]
+pub struct Pid<F> {
+    /// Proportional gain
+    pub kp: F,
+    /// Integral gain
+    pub ki: F,
+    /// Derivative gain
+    pub kd: F,
+    /// Bound of the magnitude of the integral term
+    pub integral_limit: F,
+    integral: F,
+    prev_measurement: Option<F>,
+}
+
+impl<F: FastFloat> Pid<F> {
+    /// Create a new controller with the given gains and bound of the
+    /// integral term.
+    #[inline]
+    pub fn new(kp: F, ki: F, kd: F, integral_limit: F) -> Self {
+        Pid {
+            kp,
+            ki,
+            kd,
+            integral_limit,
+            integral: F::zero(),
+            prev_measurement: None,
+        }
+    }
+
+    /// Advance the controller by the time step `dt` and return the control
+    /// output.
+    ///
+    /// The derivative term is zero in the first update after creation or
+    /// [`reset`](#method.reset). `dt` must be positive.
+    #[inline]
+    pub fn update(&mut self, setpoint: F, measurement: F, dt: F) -> F {
+        let error = setpoint - measurement;
+        let limit = self.integral_limit;
+        self.integral = (self.integral + self.ki * error * dt).max(-limit).min(limit);
+        let derivative = match self.prev_measurement {
+            Some(prev) => (measurement - prev) / dt,
+            None => F::zero(),
+        };
+        self.prev_measurement = Some(measurement);
+        self.kp * error + self.integral - self.kd * derivative
+    }
+
+    /// Return the current value of the integral term.
+    #[inline]
+    pub fn integral(&self) -> F {
+        self.integral
+    }
+
+    /// Clear the integral and derivative state.
+    #[inline]
+    pub fn reset(&mut self) {
+        self.integral = F::zero();
+        self.prev_measurement = None;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use Fast;
+
+    #[test]
+    fn pid_terms() {
+        let mut pid = Pid::new(Fast(2f32), Fast(0.5), Fast(0.25), Fast(0.625));
+        // p: 2 · 1, i: 0.5 · 1 · 1, d: none
+        assert_eq!(pid.update(Fast(1.), Fast(0.), Fast(1.)), Fast(2.5));
+        // p: 2 · 0.5, i: 0.5 + 0.5 · 0.5 clamped to 0.625, d: -0.25 · 0.5 / 1
+        assert_eq!(pid.update(Fast(1.), Fast(0.5), Fast(1.)), Fast(1.5));
+        assert_eq!(pid.integral(), Fast(0.625));
+        pid.reset();
+        assert_eq!(pid.update(Fast(0.), Fast(2.), Fast(1.)), Fast(-4.625));
+    }
+}
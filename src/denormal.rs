@@ -0,0 +1,159 @@
+//! Control of subnormal (denormal) floats.
+//!
+//! Arithmetic on subnormal floats is much slower than on normal floats on
+//! many processors, which is a problem for example in audio filters whose
+//! state decays toward zero. Subnormals can be flushed to zero explicitly
+//! with [`flush_subnormals_in_place`](fn.flush_subnormals_in_place.html), or
+//! for all float operations in a scope with
+//! [`FlushDenormals`](struct.FlushDenormals.html).
+
+use FastFloat;
+
+/// Replace each subnormal element of `xs` with a zero of the same sign, as
+/// with `Fast::flush_subnormals`.
+pub fn flush_subnormals_in_place<F: FastFloat>(xs: &mut [F]) {
+    for x in xs {
+        *x = x.flush_subnormals();
+    }
+}
+
+#[cfg(any(all(target_arch = "x86", target_feature = "sse"),
+          target_arch = "x86_64",
+          target_arch = "aarch64"))]
+pub use self::guard::FlushDenormals;
+
+#[cfg(any(all(target_arch = "x86", target_feature = "sse"),
+          target_arch = "x86_64",
+          target_arch = "aarch64"))]
+mod guard {
+    use std::arch::asm;
+    use std::marker::PhantomData;
+
+    #[cfg(any(target_arch = "x86", target_arch = "x86_64"))]
+    type Control = u32;
+    #[cfg(target_arch = "aarch64")]
+    type Control = u64;
+
+    /// MXCSR flush-to-zero (FTZ) and denormals-are-zero (DAZ) bits
+    #[cfg(any(target_arch = "x86", target_arch = "x86_64"))]
+    const FLUSH_BITS: Control = 0x8040;
+    /// FPCR flush-to-zero (FZ) bit, which covers both inputs and results
+    #[cfg(target_arch = "aarch64")]
+    const FLUSH_BITS: Control = 1 << 24;
+
+    #[cfg(any(target_arch = "x86", target_arch = "x86_64"))]
+    #[inline]
+    fn read_control() -> Control {
+        let mut csr: Control = 0;
+        unsafe {
+            asm!("stmxcsr [{}]", in(reg) &mut csr, options(nostack, preserves_flags));
+        }
+        csr
+    }
+
+    #[cfg(any(target_arch = "x86", target_arch = "x86_64"))]
+    #[inline]
+    fn write_control(csr: Control) {
+        unsafe {
+            asm!("ldmxcsr [{}]", in(reg) &csr, options(nostack, preserves_flags));
+        }
+    }
+
+    #[cfg(target_arch = "aarch64")]
+    #[inline]
+    fn read_control() -> Control {
+        let fpcr: Control;
+        unsafe {
+            asm!("mrs {}, fpcr", out(reg) fpcr, options(nostack, preserves_flags));
+        }
+        fpcr
+    }
+
+    #[cfg(target_arch = "aarch64")]
+    #[inline]
+    fn write_control(fpcr: Control) {
+        unsafe {
+            asm!("msr fpcr, {}", in(reg) fpcr, options(nostack, preserves_flags));
+        }
+    }
+
+    /// A scope guard that makes the processor treat subnormal floats as
+    /// zero.
+    ///
+    /// While the guard is alive, subnormal inputs and results of float
+    /// operations on the current thread are flushed to zero (FTZ and DAZ in
+    /// MXCSR on x86, FZ in FPCR on AArch64). The previous mode is restored
+    /// when the guard is dropped. The guard can not be sent to another
+    /// thread, since the mode is per thread.
+    ///
+    /// The compiler does not know about the float mode, and may in principle
+    /// evaluate float expressions at compile time or move operations across
+    /// the guard, so results are not guaranteed to be flushed; it is a
+    /// performance tool only.
+    ///
+    /// Only available on x86 (with SSE), x86-64 and AArch64.
+    #[derive(Debug)]
+    pub struct FlushDenormals {
+        saved: Control,
+        not_send: PhantomData<*const ()>,
+    }
+
+    impl FlushDenormals {
+        /// Enable flushing of subnormals on the current thread, until the
+        /// returned guard is dropped.
+        ///
+        /// # Safety
+        ///
+        /// The mode applies to all float code on the thread, including code
+        /// that relies on IEEE 754 subnormal arithmetic for its correctness,
+        /// for example on `x - y` being nonzero whenever `x != y`. The caller
+        /// must make sure that no such code runs on the thread while the
+        /// guard is alive, and that guards are dropped in the reverse order
+        /// of their creation and not leaked, so that the previous mode is
+        /// restored.
+        #[inline]
+        pub unsafe fn new() -> Self {
+            let saved = read_control();
+            write_control(saved | FLUSH_BITS);
+            FlushDenormals {
+                saved,
+                not_send: PhantomData,
+            }
+        }
+    }
+
+    impl Drop for FlushDenormals {
+        #[inline]
+        fn drop(&mut self) {
+            write_control(self.saved);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use Fast;
+
+    #[test]
+    fn flush() {
+        let tiny = f64::MIN_POSITIVE / 4.;
+        let mut xs = [Fast(tiny), Fast(-tiny), Fast(1.), Fast(-0.)];
+        flush_subnormals_in_place(&mut xs);
+        assert_eq!(xs, [0., 0., 1., 0.].map(Fast));
+        assert!(xs[1].get().is_sign_negative());
+    }
+
+    #[cfg(any(target_arch = "x86_64", target_arch = "aarch64"))]
+    #[test]
+    fn guard() {
+        use std::hint::black_box;
+
+        let min = f64::MIN_POSITIVE;
+        {
+            let _guard = unsafe { FlushDenormals::new() };
+            assert_eq!(black_box(min) / black_box(4.), 0.);
+        }
+        assert_eq!(black_box(min) / black_box(4.), min / 4.);
+    }
+}
@@ -0,0 +1,445 @@
+//! Signal processing helpers over sampled signals.
+
+use FastFloat;
+use Fast;
+
+/// An iterator of the means of each window of `n` consecutive elements of
+/// an iterator.
+///
+/// Created with [`windowed_mean`](fn.windowed_mean.html).
+#[derive(Clone, Debug)]
+pub struct WindowedMean<I, F> {
+    head: I,
+    tail: I,
+    n: usize,
+    scale: F,
+    sum: F,
+    /// Steps left until the sum is recomputed; `None` before the first window
+    fresh: Option<usize>,
+}
+
+impl<I, F> Iterator for WindowedMean<I, F>
+    where I: Iterator<Item=F> + Clone,
+          F: FastFloat,
+{
+    type Item = F;
+
+    fn next(&mut self) -> Option<F> {
+        match self.fresh {
+            None => {
+                let mut k = 0;
+                for x in self.head.by_ref().take(self.n) {
+                    self.sum += x;
+                    k += 1;
+                }
+                if k < self.n {
+                    return None;
+                }
+                self.fresh = Some(self.n);
+            }
+            Some(0) => {
+                self.head.next()?;
+                self.tail.next();
+                self.sum = self.tail.clone().take(self.n).fold(F::zero(), |acc, x| acc + x);
+                self.fresh = Some(self.n);
+            }
+            Some(k) => {
+                let x = self.head.next()?;
+                let old = self.tail.next()?;
+                self.sum += x - old;
+                self.fresh = Some(k - 1);
+            }
+        }
+        Some(self.sum * self.scale)
+    }
+}
+
+/// Return an iterator of the mean of each window of `n` consecutive elements
+/// of `iter`, like a moving average.
+///
+/// Windows overlap, so an iterator of `len` elements gives `len - n + 1`
+/// means (or none if `len < n`). The sum of the window is updated in O(1)
+/// per step, by adding the entering and subtracting the leaving element,
+/// and recomputed from scratch every `n` steps so that rounding errors do
+/// not accumulate. The iterator is cloned to read the leaving elements, so
+/// no buffer is needed.
+///
+/// ***Panics*** if `n` is zero.
+///
+/// ```
+/// use fast_floats::Fast;
+/// use fast_floats::dsp::windowed_mean;
+///
+/// let xs = [1., 2., 3., 4., 5.].map(Fast);
+/// let means: Vec<_> = windowed_mean(xs.iter().cloned(), 2).collect();
+/// assert_eq!(means, [1.5, 2.5, 3.5, 4.5].map(Fast));
+/// ```
+pub fn windowed_mean<I, F>(iter: I, n: usize) -> WindowedMean<I::IntoIter, F>
+    where I: IntoIterator<Item=F>,
+          I::IntoIter: Clone,
+          F: FastFloat,
+{
+    assert!(n != 0, "windowed_mean: zero window");
+    let iter = iter.into_iter();
+    WindowedMean {
+        head: iter.clone(),
+        tail: iter,
+        n,
+        scale: F::one() / F::from_usize(n),
+        sum: F::zero(),
+        fresh: None,
+    }
+}
+
+/// Replace the start of `xs` with the mean of each window of `n`
+/// consecutive elements, as in [`windowed_mean`](fn.windowed_mean.html).
+///
+/// Return the number of means, `xs.len() - n + 1`, or zero if `xs` is
+/// shorter than `n`; the elements after them are left unchanged.
+///
+/// ***Panics*** if `n` is zero.
+pub fn windowed_mean_in_place<F: FastFloat>(xs: &mut [F], n: usize) -> usize {
+    assert!(n != 0, "windowed_mean_in_place: zero window");
+    if xs.len() < n {
+        return 0;
+    }
+    let len = xs.len() - n + 1;
+    let scale = F::one() / F::from_usize(n);
+    let window_sum = |w: &[F]| w.iter().fold(F::zero(), |acc, &x| acc + x);
+    let mut sum = window_sum(&xs[..n]);
+    for i in 0..len {
+        let old = xs[i];
+        xs[i] = sum * scale;
+        if i + 1 < len {
+            if (i + 1) % n == 0 {
+                sum = window_sum(&xs[i + 1..i + 1 + n]);
+            } else {
+                sum += xs[i + n] - old;
+            }
+        }
+    }
+    len
+}
+
+/// An iterator of the indices of the peaks of a signal.
+///
+/// Created with [`find_peaks`](fn.find_peaks.html).
+#[derive(Clone, Debug)]
+pub struct Peaks<'a, F: 'a> {
+    xs: &'a [F],
+    threshold: F,
+    index: usize,
+}
+
+impl<'a, F: FastFloat> Iterator for Peaks<'a, F> {
+    type Item = usize;
+
+    fn next(&mut self) -> Option<usize> {
+        let xs = self.xs;
+        while self.index + 1 < xs.len() {
+            let i = self.index;
+            self.index += 1;
+            if !(xs[i] > xs[i - 1] && xs[i] >= self.threshold) {
+                continue;
+            }
+            // skip over a plateau, and see if it ends in a descent
+            let mut j = i + 1;
+            while j < xs.len() && xs[j] == xs[i] {
+                j += 1;
+            }
+            self.index = j;
+            if j < xs.len() && xs[j] < xs[i] {
+                return Some(i);
+            }
+        }
+        None
+    }
+}
+
+/// Return an iterator of the indices of the local maxima of `xs` that are at
+/// least `threshold`.
+///
+/// A peak is an element that is greater than its left neighbour and greater
+/// than its right neighbour, so the first and last elements are never
+/// peaks. For a flat peak, the index of its first element is reported.
+///
+/// ```
+/// use fast_floats::Fast;
+/// use fast_floats::dsp::find_peaks;
+///
+/// let xs = [0., 2., 1., 3., 3., 0., 0.5, 0.].map(Fast);
+/// let peaks: Vec<_> = find_peaks(&xs, Fast(1.)).collect();
+/// assert_eq!(peaks, [1, 3]);
+/// ```
+pub fn find_peaks<F: FastFloat>(xs: &[F], threshold: F) -> Peaks<'_, F> {
+    Peaks {
+        xs,
+        threshold,
+        index: 1,
+    }
+}
+
+/// An iterator of the indices where a signal crosses zero.
+///
+/// Created with [`zero_crossings`](fn.zero_crossings.html).
+#[derive(Clone, Debug)]
+pub struct ZeroCrossings<'a, F: 'a> {
+    xs: &'a [F],
+    hysteresis: F,
+    index: usize,
+    /// Sign of the signal, once it has left the hysteresis band
+    positive: Option<bool>,
+}
+
+impl<'a, F: FastFloat> Iterator for ZeroCrossings<'a, F> {
+    type Item = usize;
+
+    fn next(&mut self) -> Option<usize> {
+        while self.index < self.xs.len() {
+            let i = self.index;
+            let x = self.xs[i];
+            self.index += 1;
+            let positive = if x > self.hysteresis {
+                true
+            } else if x < -self.hysteresis {
+                false
+            } else {
+                continue;
+            };
+            match self.positive.replace(positive) {
+                Some(prev) if prev != positive => return Some(i),
+                _ => {}
+            }
+        }
+        None
+    }
+}
+
+/// Return an iterator of the indices where `xs` crosses zero, with
+/// `hysteresis`.
+///
+/// The signal is only considered to have changed sign when it goes from
+/// above `hysteresis` to below `-hysteresis`, or back, which rejects noise
+/// around zero. The index reported is that of the first element past the
+/// band on the new side.
+///
+/// ```
+/// use fast_floats::Fast;
+/// use fast_floats::dsp::zero_crossings;
+///
+/// let xs = [1., 0.05, -0.05, 0.05, -1., -0.5, 0.5].map(Fast);
+/// let crossings: Vec<_> = zero_crossings(&xs, Fast(0.1)).collect();
+/// assert_eq!(crossings, [4, 6]);
+/// ```
+pub fn zero_crossings<F: FastFloat>(xs: &[F], hysteresis: F) -> ZeroCrossings<'_, F> {
+    ZeroCrossings {
+        xs,
+        hysteresis,
+        index: 0,
+        positive: None,
+    }
+}
+
+/// Resample the signal `src`, sampled at `src_rate`, to `dst_rate` by
+/// linear interpolation, filling all of `out`.
+///
+/// Output sample `k` is taken at the time of source position
+/// `k · src_rate / dst_rate`; positions past the last source sample repeat
+/// it. If `src` is empty, `out` is filled with zeros. There is no
+/// anti-aliasing filter, so when downsampling, the signal should be
+/// band-limited first.
+///
+/// ```
+/// use fast_floats::Fast;
+/// use fast_floats::dsp::resample_linear;
+///
+/// let src = [0., 1., 0.].map(Fast);
+/// let mut out = [Fast(0.); 5];
+/// resample_linear(&src, Fast(1.), Fast(2.), &mut out);
+/// assert_eq!(out, [0., 0.5, 1., 0.5, 0.].map(Fast));
+/// ```
+pub fn resample_linear<F: FastFloat>(src: &[F], src_rate: F, dst_rate: F, out: &mut [F]) {
+    let last = match src.len() {
+        0 => {
+            for y in out {
+                *y = F::zero();
+            }
+            return;
+        }
+        n => n - 1,
+    };
+    let step = src_rate / dst_rate;
+    for (k, y) in out.iter_mut().enumerate() {
+        let pos = F::from_usize(k) * step;
+        let i = pos.to_usize();
+        *y = if i < last {
+            let t = pos - F::from_usize(i);
+            (src[i + 1] - src[i]) * t + src[i]
+        } else {
+            src[last]
+        };
+    }
+}
+
+#[inline(always)]
+fn to_db<F: FastFloat>(x: F, factor: F, floor_db: F) -> F {
+    let floor = (floor_db / factor).exp10();
+    factor * x.max(floor).log10()
+}
+
+#[inline(always)]
+fn from_db<F: FastFloat>(db: F, factor: F) -> F {
+    (db / factor).exp10()
+}
+
+macro_rules! impl_db {
+    ($($f:ident)+) => {
+        $(
+        impl Fast<$f> {
+            /// Convert an amplitude to decibels, `20 · log10(self)`, clamped
+            /// from below to `floor_db`.
+            ///
+            /// Amplitudes at or below the floor, including zero, give
+            /// `floor_db` instead of negative infinity.
+            #[inline]
+            pub fn amplitude_to_db(self, floor_db: Self) -> Self {
+                to_db(self, Fast(20.), floor_db)
+            }
+
+            /// Convert a power to decibels, `10 · log10(self)`, clamped from
+            /// below to `floor_db`.
+            #[inline]
+            pub fn power_to_db(self, floor_db: Self) -> Self {
+                to_db(self, Fast(10.), floor_db)
+            }
+
+            /// Convert decibels to an amplitude, `10^(self / 20)`.
+            #[inline]
+            pub fn db_to_amplitude(self) -> Self {
+                from_db(self, Fast(20.))
+            }
+
+            /// Convert decibels to a power, `10^(self / 10)`.
+            #[inline]
+            pub fn db_to_power(self) -> Self {
+                from_db(self, Fast(10.))
+            }
+        }
+        )+
+    }
+}
+
+impl_db!(f32 f64);
+
+/// Convert amplitudes to decibels in place, clamped from below to
+/// `floor_db`, as with `Fast::amplitude_to_db`.
+pub fn amplitude_to_db_in_place<F: FastFloat>(xs: &mut [F], floor_db: F) {
+    db_in_place(xs, F::from_f64(20.), floor_db)
+}
+
+/// Convert powers to decibels in place, clamped from below to `floor_db`,
+/// as with `Fast::power_to_db`.
+pub fn power_to_db_in_place<F: FastFloat>(xs: &mut [F], floor_db: F) {
+    db_in_place(xs, F::from_f64(10.), floor_db)
+}
+
+fn db_in_place<F: FastFloat>(xs: &mut [F], factor: F, floor_db: F) {
+    let floor = (floor_db / factor).exp10();
+    for x in xs {
+        *x = factor * x.max(floor).log10();
+    }
+}
+
+/// Convert decibels to amplitudes in place, as with `Fast::db_to_amplitude`.
+pub fn db_to_amplitude_in_place<F: FastFloat>(xs: &mut [F]) {
+    let scale = F::one() / F::from_f64(20.);
+    for x in xs {
+        *x = (*x * scale).exp10();
+    }
+}
+
+/// Convert decibels to powers in place, as with `Fast::db_to_power`.
+pub fn db_to_power_in_place<F: FastFloat>(xs: &mut [F]) {
+    let scale = F::one() / F::from_f64(10.);
+    for x in xs {
+        *x = (*x * scale).exp10();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use Fast;
+
+    #[test]
+    fn moving_average() {
+        let xs = [1f64, 5., 2., 8., 3., 3., 9., 0., 4., 6.].map(Fast);
+        let mut expected = [Fast(0.); 8];
+        for (e, w) in expected.iter_mut().zip(xs.windows(3)) {
+            *e = (w[0] + w[1] + w[2]) / 3.;
+        }
+        let mut means = windowed_mean(xs.iter().cloned(), 3);
+        for &e in &expected {
+            assert!((means.next().unwrap() - e).abs() < Fast(1e-12));
+        }
+        assert_eq!(means.next(), None);
+        assert_eq!(windowed_mean(xs[..2].iter().cloned(), 3).next(), None);
+
+        let mut ys = xs;
+        assert_eq!(windowed_mean_in_place(&mut ys, 3), 8);
+        for (&y, &e) in ys.iter().zip(&expected) {
+            assert!((y - e).abs() < Fast(1e-12));
+        }
+        assert_eq!(ys[8..], xs[8..]);
+    }
+
+    #[test]
+    fn peaks_and_crossings() {
+        let xs = [1., 3., 3., 5., 2., 2., 4., 4., 4., 1., 0.].map(Fast);
+        let mut peaks = find_peaks(&xs, Fast(0.));
+        assert_eq!(peaks.next(), Some(3));
+        assert_eq!(peaks.next(), Some(6));
+        assert_eq!(peaks.next(), None);
+        assert_eq!(find_peaks(&xs, Fast(4.5)).count(), 1);
+        assert_eq!(find_peaks(&xs[..1], Fast(0.)).count(), 0);
+
+        let ys = [-1f32, 0., 1., 0., -1., 1., -1.].map(Fast);
+        let mut crossings = zero_crossings(&ys, Fast(0.));
+        assert_eq!(crossings.next(), Some(2));
+        assert_eq!(crossings.next(), Some(4));
+        assert_eq!(crossings.next(), Some(5));
+        assert_eq!(crossings.next(), Some(6));
+        assert_eq!(crossings.next(), None);
+        assert_eq!(zero_crossings(&ys, Fast(1.)).count(), 0);
+    }
+
+    #[test]
+    fn resample() {
+        let src = [0., 3., 6., 9., 12.].map(Fast);
+        let mut out = [Fast(0.); 4];
+        resample_linear(&src, Fast(3.), Fast(2.), &mut out);
+        assert_eq!(out, [0., 4.5, 9., 12.].map(Fast));
+        resample_linear(&[], Fast(3.), Fast(2.), &mut out);
+        assert_eq!(out, [Fast(0.); 4]);
+    }
+
+    #[test]
+    fn decibels() {
+        let close = |a: Fast<f64>, b: f64| (a.get() - b).abs() < 1e-9;
+        assert!(close(Fast(10f64).amplitude_to_db(Fast(-120.)), 20.));
+        assert!(close(Fast(100f64).power_to_db(Fast(-120.)), 20.));
+        assert!(close(Fast(0f64).amplitude_to_db(Fast(-120.)), -120.));
+        assert!(close(Fast(-6f64).db_to_amplitude(), 0.501187233627));
+        assert!(close(Fast(30f64).db_to_power(), 1000.));
+
+        let mut xs = [1., 0.1, 0.].map(Fast);
+        amplitude_to_db_in_place(&mut xs, Fast(-60.));
+        assert!(close(xs[0], 0.) && close(xs[1], -20.) && close(xs[2], -60.));
+        db_to_amplitude_in_place(&mut xs);
+        assert!(close(xs[0], 1.) && close(xs[1], 0.1) && close(xs[2], 1e-3));
+        power_to_db_in_place(&mut xs, Fast(-20.));
+        assert!(close(xs[0], 0.) && close(xs[1], -10.) && close(xs[2], -20.));
+        db_to_power_in_place(&mut xs);
+        assert!(close(xs[1], 0.1));
+    }
+}
@@ -0,0 +1,152 @@
+//! Financial kernels: present values, internal rate of return and
+//! compounding.
+//!
+//! Rates are per period, as fractions (`0.05` for 5%), and cash flows are
+//! given at the ends of consecutive periods, starting with period zero.
+
+use FastFloat;
+use poly::eval_horner;
+
+/// Maximum number of steps taken by `newton`.
+const NEWTON_MAX_STEPS: usize = 64;
+
+/// Return the net present value of `cashflows` at `rate`.
+///
+/// That is, the sum of `cashflows[t] / (1 + rate)^t`.
+///
+/// ```
+/// use fast_floats::Fast;
+/// use fast_floats::finance::npv;
+///
+/// let flows = [-100., 60., 60.].map(Fast);
+/// assert!((npv(Fast(0.1f64), &flows).get() - 4.132231).abs() < 1e-6);
+/// ```
+#[inline]
+pub fn npv<F: FastFloat>(rate: F, cashflows: &[F]) -> F {
+    eval_horner(cashflows, F::one() / (F::one() + rate))
+}
+
+/// Return the net present value of `cashflows` at the discount factor `v`,
+/// and its derivative with respect to the rate.
+#[inline]
+fn npv_and_derivative<F: FastFloat>(v: F, cashflows: &[F]) -> (F, F) {
+    let mut p = F::zero();
+    let mut dp = F::zero();
+    for &c in cashflows.iter().rev() {
+        dp = dp * v + p;
+        p = p * v + c;
+    }
+    // dv / drate = -v²
+    (p, -v * v * dp)
+}
+
+/// Solve `f(x) = 0` with Newton's method from `x0`, where `f` returns the
+/// value and the derivative.
+///
+/// Return `None` if the iteration breaks down or doesn't converge.
+fn newton<F, G>(x0: F, mut f: G) -> Option<F>
+    where F: FastFloat,
+          G: FnMut(F) -> Option<(F, F)>,
+{
+    let tolerance = F::epsilon().sqrt();
+    let mut x = x0;
+    for _ in 0..NEWTON_MAX_STEPS {
+        let (y, dy) = f(x)?;
+        if dy == F::zero() {
+            return None;
+        }
+        let step = y / dy;
+        x -= step;
+        // convergence is quadratic, so the last step leaves an error of
+        // about tolerance²
+        if step.abs() <= tolerance * (F::one() + x.abs()) {
+            return Some(x);
+        }
+    }
+    None
+}
+
+/// Return the internal rate of return of `cashflows`: the rate at which
+/// their net present value is zero.
+///
+/// The rate is found with Newton's method starting from `guess`; `0.1` is a
+/// good start for ordinary investments. Return `None` if the iteration
+/// doesn't converge to a rate greater than `-1`. When the cash flows change
+/// sign several times there can be more than one solution, and the one
+/// closest to `guess` is usually found.
+///
+/// ```
+/// use fast_floats::Fast;
+/// use fast_floats::finance::{irr, npv};
+///
+/// let flows = [-100., 30., 40., 50.].map(Fast);
+/// let r = irr(&flows, Fast(0.1f64)).unwrap();
+/// assert!(npv(r, &flows).get().abs() < 1e-9);
+/// ```
+pub fn irr<F: FastFloat>(cashflows: &[F], guess: F) -> Option<F> {
+    newton(guess, |r| {
+        if r <= -F::one() {
+            return None;
+        }
+        Some(npv_and_derivative(F::one() / (F::one() + r), cashflows))
+    })
+}
+
+/// Compound each of `values` at `rate` over the number of periods in
+/// `times`: multiply `values[i]` by `(1 + rate)^times[i]`.
+///
+/// Periods can be fractional. If the slices have different lengths, the
+/// extra elements of the longer one are ignored.
+///
+/// ```
+/// use fast_floats::Fast;
+/// use fast_floats::finance::compound_in_place;
+///
+/// let mut xs = [100., 100.].map(Fast);
+/// compound_in_place(&mut xs, Fast(0.5f64), &[Fast(1.), Fast(2.)]);
+/// assert!((xs[1].get() - 225.).abs() < 1e-9);
+/// ```
+pub fn compound_in_place<F: FastFloat>(values: &mut [F], rate: F, times: &[F]) {
+    let growth = F::one() + rate;
+    for (x, &t) in values.iter_mut().zip(times) {
+        *x *= growth.powf(t);
+    }
+}
+
+/// Discount each of `values` at `rate` over the number of periods in
+/// `times`: divide `values[i]` by `(1 + rate)^times[i]`.
+///
+/// Periods can be fractional. If the slices have different lengths, the
+/// extra elements of the longer one are ignored.
+pub fn discount_in_place<F: FastFloat>(values: &mut [F], rate: F, times: &[F]) {
+    let growth = F::one() + rate;
+    for (x, &t) in values.iter_mut().zip(times) {
+        *x *= growth.powf(-t);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use Fast;
+
+    #[test]
+    fn npv_irr_compounding() {
+        let flows = [-100., 30., 40., 50.].map(Fast);
+        assert_eq!(npv(Fast(0.), &flows), Fast(20.));
+        let r = irr(&flows, Fast(0.1f64)).unwrap();
+        assert!((r.get() - 0.088_963_7).abs() < 1e-6);
+        let r = irr(&[-100f32, 30., 40., 50.].map(Fast), Fast(0.)).unwrap();
+        assert!((r.get() - 0.088_963_7).abs() < 1e-5);
+        // never pays back
+        assert!(irr(&[100., 30., 40.].map(Fast), Fast(0.1f64)).is_none());
+
+        let times = [0., 1., 2.5].map(Fast);
+        let mut xs = [1., 2., 3.].map(Fast);
+        compound_in_place(&mut xs, Fast(0.05f64), &times);
+        discount_in_place(&mut xs, Fast(0.05), &times);
+        for (x, y) in xs.iter().zip(&[1., 2., 3.]) {
+            assert!((x.get() - y).abs() < 1e-12);
+        }
+    }
+}
@@ -0,0 +1,153 @@
+//! Generic float trait used by the kernels in this crate.
+
+use std::ops::{
+    Add,
+    Sub,
+    Mul,
+    Div,
+    AddAssign,
+    SubAssign,
+    MulAssign,
+    DivAssign,
+    Neg,
+};
+use std::cmp::Ordering;
+use std::fmt;
+
+#[cfg(feature = "rand")]
+use rand::Rng;
+
+use Fast;
+
+mod private {
+    pub trait Sealed {}
+}
+
+/// A “fast-math” float: `Fast<f32>` or `Fast<f64>`.
+///
+/// The slice kernels and generators in this crate are generic over this
+/// trait, so that they can be written once for both widths. The trait is
+/// sealed and can not be implemented outside of this crate.
+pub trait FastFloat: private::Sealed
+    + Copy + PartialOrd + Default + fmt::Debug
+    + Add<Output=Self> + Sub<Output=Self> + Mul<Output=Self> + Div<Output=Self>
+    + AddAssign + SubAssign + MulAssign + DivAssign
+    + Neg<Output=Self>
+{
+    /// The wrapped float type
+    type Float: Copy;
+
+    fn zero() -> Self;
+    fn one() -> Self;
+    /// Machine epsilon of the float type
+    fn epsilon() -> Self;
+
+    /// Convert from `f64`, rounding if the float type is narrower.
+    fn from_f64(x: f64) -> Self;
+    /// Convert from `usize`, rounding to the nearest representable value.
+    fn from_usize(n: usize) -> Self;
+    /// Convert to `usize`, truncating toward zero and saturating at the
+    /// bounds (like an `as` cast).
+    fn to_usize(self) -> usize;
+
+    fn floor(self) -> Self;
+    fn round_to(self, ndigits: i32) -> Self;
+    /// Requires crate feature `"rand"`.
+    #[cfg(feature = "rand")]
+    fn round_stochastic<R: Rng + ?Sized>(self, rng: &mut R) -> Self;
+    fn abs(self) -> Self;
+    fn is_nan(self) -> bool;
+    fn flush_subnormals(self) -> Self;
+    fn mul_add(self, a: Self, b: Self) -> Self;
+    fn div_approx(self, rhs: Self) -> Self;
+    fn div_approx4(x: [Self; 4], y: [Self; 4]) -> [Self; 4];
+    fn powf(self, n: Self) -> Self;
+    fn sqrt(self) -> Self;
+    fn exp(self) -> Self;
+    fn exp2(self) -> Self;
+    fn exp10(self) -> Self;
+    fn ln(self) -> Self;
+    fn log2(self) -> Self;
+    fn log10(self) -> Self;
+    fn sin(self) -> Self;
+    fn cos(self) -> Self;
+    fn min(self, other: Self) -> Self;
+    fn max(self, other: Self) -> Self;
+    fn total_cmp(&self, other: &Self) -> Ordering;
+}
+
+macro_rules! impl_fast_float {
+    ($($f:ident)+) => {
+        $(
+        impl private::Sealed for Fast<$f> {}
+
+        impl FastFloat for Fast<$f> {
+            type Float = $f;
+
+            #[inline(always)]
+            fn zero() -> Self { Fast(0.) }
+            #[inline(always)]
+            fn one() -> Self { Fast(1.) }
+            #[inline(always)]
+            fn epsilon() -> Self { Fast($f::EPSILON) }
+
+            #[inline(always)]
+            fn from_f64(x: f64) -> Self { Fast(x as $f) }
+            #[inline(always)]
+            fn from_usize(n: usize) -> Self { Fast(n as $f) }
+            #[inline(always)]
+            fn to_usize(self) -> usize { self.0 as usize }
+
+            #[inline(always)]
+            fn floor(self) -> Self { Fast::<$f>::floor(self) }
+            #[inline(always)]
+            fn round_to(self, ndigits: i32) -> Self { Fast::<$f>::round_to(self, ndigits) }
+            #[cfg(feature = "rand")]
+            #[inline(always)]
+            fn round_stochastic<R: Rng + ?Sized>(self, rng: &mut R) -> Self {
+                Fast::<$f>::round_stochastic(self, rng)
+            }
+            #[inline(always)]
+            fn abs(self) -> Self { Fast::<$f>::abs(self) }
+            #[inline(always)]
+            fn is_nan(self) -> bool { Fast::<$f>::is_nan(self) }
+            #[inline(always)]
+            fn flush_subnormals(self) -> Self { Fast::<$f>::flush_subnormals(self) }
+            #[inline(always)]
+            fn mul_add(self, a: Self, b: Self) -> Self { Fast::<$f>::mul_add(self, a, b) }
+            #[inline(always)]
+            fn div_approx(self, rhs: Self) -> Self { Fast::<$f>::div_approx(self, rhs) }
+            #[inline(always)]
+            fn div_approx4(x: [Self; 4], y: [Self; 4]) -> [Self; 4] { Fast::<$f>::div_approx4(x, y) }
+            #[inline(always)]
+            fn powf(self, n: Self) -> Self { Fast::<$f>::powf(self, n) }
+            #[inline(always)]
+            fn sqrt(self) -> Self { Fast::<$f>::sqrt(self) }
+            #[inline(always)]
+            fn exp(self) -> Self { Fast::<$f>::exp(self) }
+            #[inline(always)]
+            fn exp2(self) -> Self { Fast::<$f>::exp2(self) }
+            #[inline(always)]
+            fn exp10(self) -> Self { Fast::<$f>::exp10(self) }
+            #[inline(always)]
+            fn ln(self) -> Self { Fast::<$f>::ln(self) }
+            #[inline(always)]
+            fn log2(self) -> Self { Fast::<$f>::log2(self) }
+            #[inline(always)]
+            fn log10(self) -> Self { Fast::<$f>::log10(self) }
+            #[inline(always)]
+            fn sin(self) -> Self { Fast::<$f>::sin(self) }
+            #[inline(always)]
+            fn cos(self) -> Self { Fast::<$f>::cos(self) }
+            #[inline(always)]
+            fn min(self, other: Self) -> Self { Fast::<$f>::min(self, other) }
+            #[inline(always)]
+            fn max(self, other: Self) -> Self { Fast::<$f>::max(self, other) }
+            #[inline(always)]
+            fn total_cmp(&self, other: &Self) -> Ordering { Fast::<$f>::total_cmp(self, other) }
+        }
+        )+
+    }
+}
+
+impl_fast_float!(f32 f64);
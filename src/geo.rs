@@ -0,0 +1,95 @@
+//! Great-circle distances on a sphere.
+//!
+//! Latitudes and longitudes are in radians, and distances are central
+//! angles in radians; multiply by the radius of the sphere (for example
+//! [`MEAN_EARTH_RADIUS`](constant.MEAN_EARTH_RADIUS.html)) to get a length.
+
+use FastFloat;
+use poly::eval_horner;
+
+/// Mean radius of the Earth in meters
+pub const MEAN_EARTH_RADIUS: f64 = 6_371_008.8;
+
+/// Arcsine for `x` in `0..=1`, with absolute error below `2e-8`
+/// (Abramowitz and Stegun 4.4.46).
+#[inline(always)]
+fn asin_unit<F: FastFloat>(x: F) -> F {
+    const COEFFS: [f64; 8] = [
+        1.5707963050, -0.2145988016, 0.0889789874, -0.0501743046,
+        0.0308918810, -0.0170881256, 0.0066700901, -0.0012624911,
+    ];
+    let c = COEFFS.map(F::from_f64);
+    F::from_f64(::std::f64::consts::FRAC_PI_2) - (F::one() - x).sqrt() * eval_horner(&c, x)
+}
+
+#[inline(always)]
+fn half_chord<F: FastFloat>(dlat: F, dlon: F, cos_lat1: F, cos_lat2: F) -> F {
+    let half = F::from_f64(0.5);
+    let (s_lat, s_lon) = ((dlat * half).sin(), (dlon * half).sin());
+    let a = s_lat * s_lat + cos_lat1 * cos_lat2 * (s_lon * s_lon);
+    // rounding can push a slightly above 1 for antipodal points
+    a.min(F::one())
+}
+
+/// Return the great-circle distance between two points, as the central angle
+/// in radians, using the haversine formula.
+///
+/// The arcsine uses a polynomial approximation with an absolute error of
+/// about `2e-8` radians, around 0.1 m on the Earth, which suits prefiltering
+/// and nearest neighbour search.
+///
+/// ```
+/// use fast_floats::Fast;
+/// use fast_floats::geo::{haversine, MEAN_EARTH_RADIUS};
+///
+/// let rad = |deg: f64| Fast(deg.to_radians());
+/// // Paris to London
+/// let d = haversine(rad(48.8566), rad(2.3522), rad(51.5074), rad(-0.1278));
+/// assert!((d.get() * MEAN_EARTH_RADIUS - 343_500.).abs() < 1_000.);
+/// ```
+#[inline]
+pub fn haversine<F: FastFloat>(lat1: F, lon1: F, lat2: F, lon2: F) -> F {
+    let a = half_chord(lat2 - lat1, lon2 - lon1, lat1.cos(), lat2.cos());
+    F::from_f64(2.) * asin_unit(a.sqrt())
+}
+
+/// Compute the great-circle distance from the point `(lat0, lon0)` to each of
+/// the points given by `lats` and `lons`, as with
+/// [`haversine`](fn.haversine.html), writing them to `out`.
+///
+/// If the slices have different lengths, the extra elements of the longer
+/// ones are ignored.
+pub fn haversine_many<F: FastFloat>(lat0: F, lon0: F, lats: &[F], lons: &[F], out: &mut [F]) {
+    let cos_lat0 = lat0.cos();
+    let two = F::from_f64(2.);
+    for ((d, &lat), &lon) in out.iter_mut().zip(lats).zip(lons) {
+        let a = half_chord(lat - lat0, lon - lon0, cos_lat0, lat.cos());
+        *d = two * asin_unit(a.sqrt());
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use Fast;
+
+    #[test]
+    fn great_circle() {
+        use std::f64::consts::{FRAC_PI_2, PI};
+
+        let d = haversine(Fast(0.), Fast(0.), Fast(0.), Fast(FRAC_PI_2));
+        assert!((d.get() - FRAC_PI_2).abs() < 1e-7);
+        let d = haversine(Fast(FRAC_PI_2), Fast(0.), Fast(-FRAC_PI_2), Fast(0.));
+        assert!((d.get() - PI).abs() < 1e-7);
+        assert!(haversine(Fast(0.5f64), Fast(1.), Fast(0.5), Fast(1.)).get().abs() < 1e-7);
+
+        let lats = [0.1f64, -0.4, 1.2].map(Fast);
+        let lons = [2.0, 0.3, -2.5].map(Fast);
+        let mut out = [Fast(0.); 3];
+        haversine_many(Fast(0.7), Fast(-0.2), &lats, &lons, &mut out);
+        for i in 0..3 {
+            let d = haversine(Fast(0.7), Fast(-0.2), lats[i], lons[i]);
+            assert!((out[i] - d).abs() < Fast(1e-12));
+        }
+    }
+}
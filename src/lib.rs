@@ -21,10 +21,28 @@
 extern crate num_traits;
 
 #[cfg(feature = "num-traits")]
-use num_traits::Zero;
+use num_traits::{Zero, One, Num, Signed, FromPrimitive, ToPrimitive, NumCast, Float, FloatConst};
+#[cfg(feature = "num-traits")]
+use std::num::FpCategory;
+
+#[cfg(feature = "rand")]
+extern crate rand;
+
+#[cfg(all(test, feature = "rustfft"))]
+extern crate rustfft;
+
+#[cfg(feature = "derive")]
+extern crate fast_floats_derive;
+
+#[cfg(feature = "argmin")]
+extern crate argmin;
+#[cfg(feature = "argmin")]
+extern crate argmin_math;
 
 extern crate core as std;
 
+use std::cmp::Ordering;
+use std::iter::{Sum, Product};
 use std::intrinsics::{self, fadd_fast, fsub_fast, fmul_fast, fdiv_fast, frem_fast};
 use std::ops::{
     Add,
@@ -40,10 +58,46 @@ use std::ops::{
     Neg,
 };
 
+#[cfg(feature = "argmin")]
+mod argmin_support;
+#[cfg(feature = "vecmath")]
+mod vecmath;
+pub mod close;
+pub mod color;
+pub mod control;
+pub mod denormal;
+pub mod dsp;
+pub mod finance;
+mod float;
+pub mod geo;
+pub mod mat;
+pub mod nn;
+pub mod noise;
+pub mod physics;
+pub mod poly;
+pub mod recip;
+pub mod reduce;
+pub mod rng;
+pub mod round;
+pub mod similarity;
+pub mod simd;
+pub mod space;
+pub mod spline;
+pub mod stats;
+pub mod validate;
+
+pub use float::FastFloat;
+#[cfg(feature = "derive")]
+pub use fast_floats_derive::FastOps;
+
 /// “fast-math” wrapper for f32 and f64.
 ///
 /// The `Fast` type enforces no invariant and can hold any f32, f64 values.
 /// See crate docs for more details.
+///
+/// The arithmetic operators also accept an integer operand, as in `x * n`;
+/// the integer is converted to the float type with `as`, rounding to the
+/// nearest representable value if it is too large to be exact.
 #[repr(transparent)]
 #[derive(Copy, Clone, PartialEq, PartialOrd, Default)]
 pub struct Fast<F>(pub F);
@@ -161,6 +215,43 @@ impl_op! {
     Rem, rem, frem_fast;
 }
 
+// Fast<F> + integer and integer + Fast<F>, converting the integer with `as`
+macro_rules! impl_int_op {
+    ($($f:ident)+; $ints:tt) => {
+        $(
+        impl_int_op!(@float $f $ints);
+        )+
+    };
+    (@float $f:ident [$($i:ident)+]) => {
+        $(
+        impl_int_op!(@op $f $i Add add);
+        impl_int_op!(@op $f $i Sub sub);
+        impl_int_op!(@op $f $i Mul mul);
+        impl_int_op!(@op $f $i Div div);
+        impl_int_op!(@op $f $i Rem rem);
+        )+
+    };
+    (@op $f:ident $i:ident $name:ident $method:ident) => {
+        impl $name<$i> for Fast<$f> {
+            type Output = Self;
+            #[inline(always)]
+            fn $method(self, rhs: $i) -> Self::Output {
+                self.$method(rhs as $f)
+            }
+        }
+
+        impl $name<Fast<$f>> for $i {
+            type Output = Fast<$f>;
+            #[inline(always)]
+            fn $method(self, rhs: Fast<$f>) -> Self::Output {
+                Fast(self as $f).$method(rhs)
+            }
+        }
+    };
+}
+
+impl_int_op!(f32 f64; [i8 i16 i32 i64 isize u8 u16 u32 u64 usize]);
+
 impl_assignop! {
     AddAssign, add_assign, +, Add;
     SubAssign, sub_assign, -, Sub;
@@ -169,6 +260,70 @@ impl_assignop! {
     RemAssign, rem_assign, %, Rem;
 }
 
+// Precise arithmetic as `const fn`s: the fast-math intrinsics can not be
+// used in constant evaluation, so these use regular IEEE arithmetic.
+macro_rules! impl_const_ops {
+    ($($f:ident)+) => {
+        $(
+        impl Fast<$f> {
+            /// Addition with precise (not fast-math) semantics, usable in
+            /// constant expressions.
+            ///
+            /// This lets tables and derived constants of `Fast` values be
+            /// computed at compile time.
+            ///
+            /// ```
+            /// use fast_floats::Fast;
+            ///
+            /// const PI: Fast<f64> = Fast(std::f64::consts::PI);
+            /// const TAU: Fast<f64> = PI.const_add(PI);
+            /// assert_eq!(TAU.get(), std::f64::consts::TAU);
+            /// ```
+            #[inline(always)]
+            pub const fn const_add(self, rhs: Self) -> Self {
+                Fast(self.0 + rhs.0)
+            }
+
+            /// Subtraction with precise (not fast-math) semantics, usable in
+            /// constant expressions.
+            #[inline(always)]
+            pub const fn const_sub(self, rhs: Self) -> Self {
+                Fast(self.0 - rhs.0)
+            }
+
+            /// Multiplication with precise (not fast-math) semantics, usable in
+            /// constant expressions.
+            #[inline(always)]
+            pub const fn const_mul(self, rhs: Self) -> Self {
+                Fast(self.0 * rhs.0)
+            }
+
+            /// Division with precise (not fast-math) semantics, usable in
+            /// constant expressions.
+            #[inline(always)]
+            pub const fn const_div(self, rhs: Self) -> Self {
+                Fast(self.0 / rhs.0)
+            }
+
+            /// Remainder with precise (not fast-math) semantics, usable in
+            /// constant expressions.
+            #[inline(always)]
+            pub const fn const_rem(self, rhs: Self) -> Self {
+                Fast(self.0 % rhs.0)
+            }
+
+            /// Negation, usable in constant expressions.
+            #[inline(always)]
+            pub const fn const_neg(self) -> Self {
+                Fast(-self.0)
+            }
+        }
+        )+
+    }
+}
+
+impl_const_ops!(f32 f64);
+
 impl Neg for Fast<f64> {
     type Output = Self;
 
@@ -186,6 +341,42 @@ impl Neg for Fast<f32> {
     }
 }
 
+macro_rules! impl_iter_ops {
+    ($($f:ident)+) => {
+        $(
+        impl Sum for Fast<$f> {
+            #[inline]
+            fn sum<I: Iterator<Item=Self>>(iter: I) -> Self {
+                iter.fold(Fast(0.), |acc, x| acc + x)
+            }
+        }
+
+        impl<'a> Sum<&'a Fast<$f>> for Fast<$f> {
+            #[inline]
+            fn sum<I: Iterator<Item=&'a Self>>(iter: I) -> Self {
+                iter.fold(Fast(0.), |acc, &x| acc + x)
+            }
+        }
+
+        impl Product for Fast<$f> {
+            #[inline]
+            fn product<I: Iterator<Item=Self>>(iter: I) -> Self {
+                iter.fold(Fast(1.), |acc, x| acc * x)
+            }
+        }
+
+        impl<'a> Product<&'a Fast<$f>> for Fast<$f> {
+            #[inline]
+            fn product<I: Iterator<Item=&'a Self>>(iter: I) -> Self {
+                iter.fold(Fast(1.), |acc, &x| acc * x)
+            }
+        }
+        )+
+    }
+}
+
+impl_iter_ops!(f32 f64);
+
 impl Fast<f32> {
     #[inline(always)]
     pub fn floor(self) -> Self {
@@ -222,6 +413,16 @@ impl Fast<f32> {
         self.0.is_nan()
     }
 
+    /// Replace a subnormal value with a zero of the same sign.
+    #[inline(always)]
+    pub fn flush_subnormals(self) -> Self {
+        if self.abs().0 < f32::MIN_POSITIVE {
+            Self(unsafe { intrinsics::copysignf32(0., self.0) })
+        } else {
+            self
+        }
+    }
+
     #[inline(always)]
     pub fn signum(self) -> Self {
         if self.is_nan() {
@@ -246,6 +447,7 @@ impl Fast<f32> {
         Self(unsafe { intrinsics::powif32(self.0, n) })
     }
 
+    #[cfg(not(feature = "vecmath"))]
     #[inline(always)]
     pub fn powf(self, n: Self) -> Self {
         Self(unsafe { intrinsics::powf32(self.0, n.0) })
@@ -256,6 +458,7 @@ impl Fast<f32> {
         Self(unsafe { intrinsics::sqrtf32(self.0) })
     }
 
+    #[cfg(not(feature = "vecmath"))]
     #[inline(always)]
     pub fn exp(self) -> Self {
         Self(unsafe { intrinsics::expf32(self.0) })
@@ -266,6 +469,12 @@ impl Fast<f32> {
         Self(unsafe { intrinsics::exp2f32(self.0) })
     }
 
+    #[inline(always)]
+    pub fn exp10(self) -> Self {
+        (self * std::f32::consts::LOG2_10).exp2()
+    }
+
+    #[cfg(not(feature = "vecmath"))]
     #[inline(always)]
     pub fn ln(self) -> Self {
         Self(unsafe { intrinsics::logf32(self.0) })
@@ -286,11 +495,13 @@ impl Fast<f32> {
         Self(unsafe { intrinsics::log10f32(self.0) })
     }
 
+    #[cfg(not(feature = "vecmath"))]
     #[inline(always)]
     pub fn sin(self) -> Self {
         Self(unsafe { intrinsics::sinf32(self.0) })
     }
 
+    #[cfg(not(feature = "vecmath"))]
     #[inline(always)]
     pub fn cos(self) -> Self {
         Self(unsafe { intrinsics::cosf32(self.0) })
@@ -333,6 +544,11 @@ impl Fast<f32> {
             _ => other,
         }
     }
+
+    #[inline(always)]
+    pub fn total_cmp(&self, other: &Self) -> Ordering {
+        self.0.total_cmp(&other.0)
+    }
 }
 impl Fast<f64> {
     #[inline(always)]
@@ -370,6 +586,16 @@ impl Fast<f64> {
         self.0.is_nan()
     }
 
+    /// Replace a subnormal value with a zero of the same sign.
+    #[inline(always)]
+    pub fn flush_subnormals(self) -> Self {
+        if self.abs().0 < f64::MIN_POSITIVE {
+            Self(unsafe { intrinsics::copysignf64(0., self.0) })
+        } else {
+            self
+        }
+    }
+
     #[inline(always)]
     pub fn signum(self) -> Self {
         if self.is_nan() {
@@ -394,6 +620,7 @@ impl Fast<f64> {
         Self(unsafe { intrinsics::powif64(self.0, n) })
     }
 
+    #[cfg(not(feature = "vecmath"))]
     #[inline(always)]
     pub fn powf(self, n: Self) -> Self {
         Self(unsafe { intrinsics::powf64(self.0, n.0) })
@@ -404,6 +631,7 @@ impl Fast<f64> {
         Self(unsafe { intrinsics::sqrtf64(self.0) })
     }
 
+    #[cfg(not(feature = "vecmath"))]
     #[inline(always)]
     pub fn exp(self) -> Self {
         Self(unsafe { intrinsics::expf64(self.0) })
@@ -414,6 +642,12 @@ impl Fast<f64> {
         Self(unsafe { intrinsics::exp2f64(self.0) })
     }
 
+    #[inline(always)]
+    pub fn exp10(self) -> Self {
+        (self * std::f64::consts::LOG2_10).exp2()
+    }
+
+    #[cfg(not(feature = "vecmath"))]
     #[inline(always)]
     pub fn ln(self) -> Self {
         Self(unsafe { intrinsics::logf64(self.0) })
@@ -434,11 +668,13 @@ impl Fast<f64> {
         Self(unsafe { intrinsics::log10f64(self.0) })
     }
 
+    #[cfg(not(feature = "vecmath"))]
     #[inline(always)]
     pub fn sin(self) -> Self {
         Self(unsafe { intrinsics::sinf64(self.0) })
     }
 
+    #[cfg(not(feature = "vecmath"))]
     #[inline(always)]
     pub fn cos(self) -> Self {
         Self(unsafe { intrinsics::cosf64(self.0) })
@@ -481,6 +717,11 @@ impl Fast<f64> {
             _ => other,
         }
     }
+
+    #[inline(always)]
+    pub fn total_cmp(&self, other: &Self) -> Ordering {
+        self.0.total_cmp(&other.0)
+    }
 }
 
 /*
@@ -494,7 +735,7 @@ impl<Z> Zero for Fast<Z> where Z: Zero {
 #[cfg(feature = "num-traits")]
 impl Zero for Fast<f64> {
     #[inline(always)]
-    fn zero() -> Self { Fast(<_>::zero()) }
+    fn zero() -> Self { Fast(Zero::zero()) }
 
     #[inline(always)]
     fn is_zero(&self) -> bool { self.get().is_zero() }
@@ -502,12 +743,182 @@ impl Zero for Fast<f64> {
 #[cfg(feature = "num-traits")]
 impl Zero for Fast<f32> {
     #[inline(always)]
-    fn zero() -> Self { Fast(<_>::zero()) }
+    fn zero() -> Self { Fast(Zero::zero()) }
 
     #[inline(always)]
     fn is_zero(&self) -> bool { self.get().is_zero() }
 }
 
+// One, Num, Signed and FromPrimitive make `Fast` usable as a generic number,
+// for example as the scalar of `rustfft`.
+#[cfg(feature = "num-traits")]
+macro_rules! impl_num_traits {
+    ($($f:ident)+) => {
+        $(
+        impl One for Fast<$f> {
+            #[inline(always)]
+            fn one() -> Self { Fast(One::one()) }
+        }
+
+        impl Num for Fast<$f> {
+            type FromStrRadixErr = <$f as Num>::FromStrRadixErr;
+
+            fn from_str_radix(s: &str, radix: u32) -> Result<Self, Self::FromStrRadixErr> {
+                <$f as Num>::from_str_radix(s, radix).map(Fast)
+            }
+        }
+
+        impl Signed for Fast<$f> {
+            #[inline(always)]
+            fn abs(&self) -> Self { Fast::<$f>::abs(*self) }
+
+            #[inline(always)]
+            fn abs_sub(&self, other: &Self) -> Self {
+                if *self <= *other { Fast(0.) } else { *self - *other }
+            }
+
+            #[inline(always)]
+            fn signum(&self) -> Self { Fast::<$f>::signum(*self) }
+
+            #[inline(always)]
+            fn is_positive(&self) -> bool { self.0.is_sign_positive() }
+
+            #[inline(always)]
+            fn is_negative(&self) -> bool { self.0.is_sign_negative() }
+        }
+
+        impl FromPrimitive for Fast<$f> {
+            #[inline(always)]
+            fn from_i64(n: i64) -> Option<Self> { <$f>::from_i64(n).map(Fast) }
+
+            #[inline(always)]
+            fn from_u64(n: u64) -> Option<Self> { <$f>::from_u64(n).map(Fast) }
+
+            #[inline(always)]
+            fn from_f32(x: f32) -> Option<Self> { <$f>::from_f32(x).map(Fast) }
+
+            #[inline(always)]
+            fn from_f64(x: f64) -> Option<Self> { <$f>::from_f64(x).map(Fast) }
+        }
+        )+
+    }
+}
+
+#[cfg(feature = "num-traits")]
+impl_num_traits!(f32 f64);
+
+// Float, FloatConst and ToPrimitive complete the num-traits float interface,
+// as required by generic numeric code such as the `argmin` solvers. Methods
+// that `Fast` has use its fast versions, the rest use the wrapped float.
+#[cfg(feature = "num-traits")]
+macro_rules! impl_num_float {
+    ($($f:ident)+) => {
+        $(
+        impl ToPrimitive for Fast<$f> {
+            #[inline(always)]
+            fn to_i64(&self) -> Option<i64> { self.0.to_i64() }
+
+            #[inline(always)]
+            fn to_u64(&self) -> Option<u64> { self.0.to_u64() }
+
+            #[inline(always)]
+            fn to_f32(&self) -> Option<f32> { self.0.to_f32() }
+
+            #[inline(always)]
+            fn to_f64(&self) -> Option<f64> { self.0.to_f64() }
+        }
+
+        impl NumCast for Fast<$f> {
+            #[inline(always)]
+            fn from<T: ToPrimitive>(n: T) -> Option<Self> { <$f as NumCast>::from(n).map(Fast) }
+        }
+
+        impl FloatConst for Fast<$f> {
+            impl_num_float!(@consts $f
+                E FRAC_1_PI FRAC_1_SQRT_2 FRAC_2_PI FRAC_2_SQRT_PI FRAC_PI_2 FRAC_PI_3
+                FRAC_PI_4 FRAC_PI_6 FRAC_PI_8 LN_10 LN_2 LOG10_E LOG2_E PI SQRT_2
+                TAU LOG10_2 LOG2_10);
+        }
+
+        impl Float for Fast<$f> {
+            impl_num_float!(@wrapped $f nan infinity neg_infinity neg_zero min_value
+                min_positive_value epsilon max_value);
+            impl_num_float!(@fast $f floor ceil round trunc fract abs signum sqrt exp exp2
+                ln log2 log10 sin cos asinh acosh);
+            impl_num_float!(@unary $f recip cbrt tan asin acos atan exp_m1 ln_1p sinh cosh
+                tanh atanh);
+            impl_num_float!(@binary $f hypot atan2);
+
+            #[inline(always)]
+            fn is_nan(self) -> bool { self.0.is_nan() }
+            #[inline(always)]
+            fn is_infinite(self) -> bool { self.0.is_infinite() }
+            #[inline(always)]
+            fn is_finite(self) -> bool { self.0.is_finite() }
+            #[inline(always)]
+            fn is_normal(self) -> bool { self.0.is_normal() }
+            #[inline(always)]
+            fn classify(self) -> FpCategory { self.0.classify() }
+            #[inline(always)]
+            fn is_sign_positive(self) -> bool { self.0.is_sign_positive() }
+            #[inline(always)]
+            fn is_sign_negative(self) -> bool { self.0.is_sign_negative() }
+            #[inline(always)]
+            fn mul_add(self, a: Self, b: Self) -> Self { Fast::<$f>::mul_add(self, a, b) }
+            #[inline(always)]
+            fn powi(self, n: i32) -> Self { Fast::<$f>::powi(self, n) }
+            #[inline(always)]
+            fn powf(self, n: Self) -> Self { Fast::<$f>::powf(self, n) }
+            #[inline(always)]
+            fn log(self, base: Self) -> Self { Fast::<$f>::log(self, base) }
+            #[inline(always)]
+            fn max(self, other: Self) -> Self { Fast::<$f>::max(self, other) }
+            #[inline(always)]
+            fn min(self, other: Self) -> Self { Fast::<$f>::min(self, other) }
+            #[inline(always)]
+            fn abs_sub(self, other: Self) -> Self { Signed::abs_sub(&self, &other) }
+            #[inline(always)]
+            fn sin_cos(self) -> (Self, Self) { Fast::<$f>::sin_cos(self) }
+            #[inline(always)]
+            fn integer_decode(self) -> (u64, i16, i8) { Float::integer_decode(self.0) }
+        }
+        )+
+    };
+    (@consts $f:ident $($c:ident)+) => {
+        $(
+        #[inline(always)]
+        fn $c() -> Self { Fast(<$f as FloatConst>::$c()) }
+        )+
+    };
+    (@wrapped $f:ident $($m:ident)+) => {
+        $(
+        #[inline(always)]
+        fn $m() -> Self { Fast(<$f as Float>::$m()) }
+        )+
+    };
+    (@fast $f:ident $($m:ident)+) => {
+        $(
+        #[inline(always)]
+        fn $m(self) -> Self { Fast::<$f>::$m(self) }
+        )+
+    };
+    (@unary $f:ident $($m:ident)+) => {
+        $(
+        #[inline(always)]
+        fn $m(self) -> Self { Fast(<$f as Float>::$m(self.0)) }
+        )+
+    };
+    (@binary $f:ident $($m:ident)+) => {
+        $(
+        #[inline(always)]
+        fn $m(self, other: Self) -> Self { Fast(<$f as Float>::$m(self.0, other.0)) }
+        )+
+    };
+}
+
+#[cfg(feature = "num-traits")]
+impl_num_float!(f32 f64);
+
 use std::fmt;
 macro_rules! impl_format {
     ($($name:ident)+) => {
@@ -541,4 +952,69 @@ mod tests {
     fn each_op() {
         test_op!(+ - * / %);
     }
+
+    #[test]
+    fn int_ops() {
+        let n = 3;
+        assert_eq!(Fast(1.5) * n, Fast(4.5));
+        assert_eq!(Fast(1.5f32) / 2u32, Fast(0.75));
+        assert_eq!(2i64 - Fast(0.5), Fast(1.5));
+        assert_eq!(Fast(7.) % 4usize, Fast(3.));
+        let mut x = Fast(1f32);
+        x += 1u8;
+        x *= -3i16;
+        assert_eq!(x, Fast(-6.));
+    }
+
+    #[test]
+    fn const_ops() {
+        const ONE: Fast<f32> = Fast(1.);
+        const TABLE: [Fast<f32>; 4] = [
+            ONE.const_add(ONE),
+            ONE.const_sub(Fast(0.5)),
+            ONE.const_mul(Fast(3.)).const_neg(),
+            ONE.const_div(Fast(4.)).const_rem(Fast(0.125)),
+        ];
+        assert_eq!(TABLE, [Fast(2.), Fast(0.5), Fast(-3.), Fast(0.)]);
+        assert_eq!(TABLE[0].get(), 2.);
+    }
+
+    #[cfg(feature = "rustfft")]
+    #[test]
+    fn rustfft_scalar() {
+        use rustfft::FftPlanner;
+        use rustfft::num_complex::Complex;
+
+        let mut buf = [Complex::new(Fast(0f32), Fast(0.)); 8];
+        buf[1].re = Fast(1.);
+        FftPlanner::new().plan_fft_forward(buf.len()).process(&mut buf);
+        for (k, z) in buf.iter().enumerate() {
+            let angle = -2. * std::f32::consts::PI * k as f32 / 8.;
+            assert!((z.re.get() - angle.cos()).abs() < 1e-6);
+            assert!((z.im.get() - angle.sin()).abs() < 1e-6);
+        }
+    }
+
+    #[cfg(feature = "derive")]
+    #[test]
+    fn derive_ops() {
+        #[derive(Copy, Clone, Debug, PartialEq, FastOps)]
+        struct Particle {
+            x: Fast<f32>,
+            v: Fast<f32>,
+        }
+
+        #[derive(Copy, Clone, Debug, PartialEq, FastOps)]
+        #[fast_ops(scalar = Fast<f32>)]
+        struct Pair(Particle, Fast<f32>);
+
+        let p = Particle { x: Fast(1.), v: Fast(2.) };
+        let mut q = p + p * Fast(0.5) - p / Fast(2.);
+        assert_eq!(q, p);
+        q *= Fast(2.);
+        q -= p;
+        assert_eq!(-q, Particle { x: Fast(-1.), v: Fast(-2.) });
+        let pair = Pair(p, Fast(3.)) * Pair(p, Fast(2.));
+        assert_eq!(pair, Pair(Particle { x: Fast(1.), v: Fast(4.) }, Fast(6.)));
+    }
 }
@@ -0,0 +1,231 @@
+//! Small square matrices: determinant, inverse, transpose and matrix–vector
+//! product.
+//!
+//! Matrices are arrays of rows, `[[F; N]; N]`, so `m[i][j]` is the element
+//! in row `i` and column `j`, and vectors are column vectors `[F; N]`. The
+//! differences of products in the determinants and inverses use fused
+//! multiply-adds.
+
+use FastFloat;
+
+/// Return `a · b - c · d`.
+///
+/// The rounding error of `c · d` is recovered with a fused multiply-add
+/// (Kahan's method), so the result is accurate even when the two products
+/// nearly cancel.
+#[inline(always)]
+fn diff_of_products<F: FastFloat>(a: F, b: F, c: F, d: F) -> F {
+    let cd = c * d;
+    let err = (-c).mul_add(d, cd);
+    a.mul_add(b, -cd) + err
+}
+
+/// Return the determinant of the 2 × 2 matrix `m`.
+#[inline]
+pub fn det2<F: FastFloat>(m: &[[F; 2]; 2]) -> F {
+    diff_of_products(m[0][0], m[1][1], m[0][1], m[1][0])
+}
+
+/// Return the determinant of the 3 × 3 matrix `m`.
+///
+/// ```
+/// use fast_floats::Fast;
+/// use fast_floats::mat::det3;
+///
+/// let m = [[2., 0., 1.], [1., 3., 2.], [1., 1., 2.]].map(|r| r.map(Fast));
+/// assert_eq!(det3(&m), Fast(6.));
+/// ```
+#[inline]
+pub fn det3<F: FastFloat>(m: &[[F; 3]; 3]) -> F {
+    let c = cross(&m[1], &m[2]);
+    dot3(&m[0], &c)
+}
+
+/// Return the determinant of the 4 × 4 matrix `m`.
+#[inline]
+pub fn det4<F: FastFloat>(m: &[[F; 4]; 4]) -> F {
+    let (s, c) = subfactors(m);
+    det4_from(&s, &c)
+}
+
+/// Return the inverse of the 2 × 2 matrix `m`, or `None` if its determinant
+/// is zero.
+///
+/// Nearly singular matrices give inaccurate results.
+#[inline]
+pub fn inverse2<F: FastFloat>(m: &[[F; 2]; 2]) -> Option<[[F; 2]; 2]> {
+    let det = det2(m);
+    if det == F::zero() {
+        return None;
+    }
+    let r = F::one() / det;
+    Some([[m[1][1] * r, -m[0][1] * r],
+          [-m[1][0] * r, m[0][0] * r]])
+}
+
+/// Return the inverse of the 3 × 3 matrix `m`, or `None` if its determinant
+/// is zero.
+///
+/// Nearly singular matrices give inaccurate results.
+#[inline]
+pub fn inverse3<F: FastFloat>(m: &[[F; 3]; 3]) -> Option<[[F; 3]; 3]> {
+    // the cross products of pairs of rows are the columns of the adjugate
+    let c0 = cross(&m[1], &m[2]);
+    let c1 = cross(&m[2], &m[0]);
+    let c2 = cross(&m[0], &m[1]);
+    let det = dot3(&m[0], &c0);
+    if det == F::zero() {
+        return None;
+    }
+    let r = F::one() / det;
+    Some([[c0[0] * r, c1[0] * r, c2[0] * r],
+          [c0[1] * r, c1[1] * r, c2[1] * r],
+          [c0[2] * r, c1[2] * r, c2[2] * r]])
+}
+
+/// Return the inverse of the 4 × 4 matrix `m`, or `None` if its determinant
+/// is zero.
+///
+/// Nearly singular matrices give inaccurate results.
+///
+/// ```
+/// use fast_floats::Fast;
+/// use fast_floats::mat::{inverse4, mul_vec};
+///
+/// // translation by (1, 2, 3) in homogeneous coordinates
+/// let m = [[1., 0., 0., 1.],
+///          [0., 1., 0., 2.],
+///          [0., 0., 1., 3.],
+///          [0., 0., 0., 1.]].map(|r| r.map(Fast));
+/// let inv = inverse4(&m).unwrap();
+/// let p = [1., 1., 1., 1.].map(Fast);
+/// assert_eq!(mul_vec(&inv, &p), [0., -1., -2., 1.].map(Fast));
+/// ```
+#[inline]
+pub fn inverse4<F: FastFloat>(m: &[[F; 4]; 4]) -> Option<[[F; 4]; 4]> {
+    let (s, c) = subfactors(m);
+    let det = det4_from(&s, &c);
+    if det == F::zero() {
+        return None;
+    }
+    let r = F::one() / det;
+    // a · x - b · y + c · z
+    let t = |a: F, x: F, b: F, y: F, c: F, z: F| (diff_of_products(a, x, b, y) + c * z) * r;
+    Some([
+        [t(m[1][1], c[5], m[1][2], c[4], m[1][3], c[3]),
+         t(m[0][2], c[4], m[0][1], c[5], -m[0][3], c[3]),
+         t(m[3][1], s[5], m[3][2], s[4], m[3][3], s[3]),
+         t(m[2][2], s[4], m[2][1], s[5], -m[2][3], s[3])],
+        [t(m[1][2], c[2], m[1][0], c[5], -m[1][3], c[1]),
+         t(m[0][0], c[5], m[0][2], c[2], m[0][3], c[1]),
+         t(m[3][2], s[2], m[3][0], s[5], -m[3][3], s[1]),
+         t(m[2][0], s[5], m[2][2], s[2], m[2][3], s[1])],
+        [t(m[1][0], c[4], m[1][1], c[2], m[1][3], c[0]),
+         t(m[0][1], c[2], m[0][0], c[4], -m[0][3], c[0]),
+         t(m[3][0], s[4], m[3][1], s[2], m[3][3], s[0]),
+         t(m[2][1], s[2], m[2][0], s[4], -m[2][3], s[0])],
+        [t(m[1][1], c[1], m[1][0], c[3], -m[1][2], c[0]),
+         t(m[0][0], c[3], m[0][1], c[1], m[0][2], c[0]),
+         t(m[3][1], s[1], m[3][0], s[3], -m[3][2], s[0]),
+         t(m[2][0], s[3], m[2][1], s[1], m[2][2], s[0])],
+    ])
+}
+
+/// Return the transpose of `m`.
+#[inline]
+pub fn transpose<F: Copy, const N: usize>(m: &[[F; N]; N]) -> [[F; N]; N] {
+    let mut t = *m;
+    for (i, row) in m.iter().enumerate() {
+        for (j, &x) in row.iter().enumerate() {
+            t[j][i] = x;
+        }
+    }
+    t
+}
+
+/// Return the product of the matrix `m` and the column vector `v`.
+#[inline]
+pub fn mul_vec<F: FastFloat, const N: usize>(m: &[[F; N]; N], v: &[F; N]) -> [F; N] {
+    let mut out = [F::zero(); N];
+    for (o, row) in out.iter_mut().zip(m) {
+        *o = row.iter().zip(v).fold(F::zero(), |acc, (&a, &x)| acc + a * x);
+    }
+    out
+}
+
+#[inline(always)]
+fn cross<F: FastFloat>(a: &[F; 3], b: &[F; 3]) -> [F; 3] {
+    [diff_of_products(a[1], b[2], a[2], b[1]),
+     diff_of_products(a[2], b[0], a[0], b[2]),
+     diff_of_products(a[0], b[1], a[1], b[0])]
+}
+
+#[inline(always)]
+fn dot3<F: FastFloat>(a: &[F; 3], b: &[F; 3]) -> F {
+    a[0] * b[0] + a[1] * b[1] + a[2] * b[2]
+}
+
+/// Return the 2 × 2 minors of the top two rows (`s`) and the bottom two
+/// rows (`c`) of `m`, from which its determinant and adjugate are formed.
+#[inline(always)]
+fn subfactors<F: FastFloat>(m: &[[F; 4]; 4]) -> ([F; 6], [F; 6]) {
+    let minor = |r0: usize, r1: usize, i: usize, j: usize| {
+        diff_of_products(m[r0][i], m[r1][j], m[r1][i], m[r0][j])
+    };
+    let s = [minor(0, 1, 0, 1), minor(0, 1, 0, 2), minor(0, 1, 0, 3),
+             minor(0, 1, 1, 2), minor(0, 1, 1, 3), minor(0, 1, 2, 3)];
+    let c = [minor(2, 3, 0, 1), minor(2, 3, 0, 2), minor(2, 3, 0, 3),
+             minor(2, 3, 1, 2), minor(2, 3, 1, 3), minor(2, 3, 2, 3)];
+    (s, c)
+}
+
+#[inline(always)]
+fn det4_from<F: FastFloat>(s: &[F; 6], c: &[F; 6]) -> F {
+    diff_of_products(s[0], c[5], s[1], c[4])
+        + diff_of_products(s[2], c[3], -s[3], c[2])
+        + diff_of_products(s[5], c[0], s[4], c[1])
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use Fast;
+
+    fn mul<const N: usize>(a: &[[Fast<f64>; N]; N], b: &[[Fast<f64>; N]; N]) -> [[Fast<f64>; N]; N] {
+        let bt = transpose(b);
+        a.map(|row| mul_vec(&bt, &row))
+    }
+
+    fn assert_identity<const N: usize>(m: &[[Fast<f64>; N]; N]) {
+        for (i, row) in m.iter().enumerate() {
+            for (j, x) in row.iter().enumerate() {
+                let want = if i == j { 1. } else { 0. };
+                assert!((x.get() - want).abs() < 1e-12, "{:?}", m);
+            }
+        }
+    }
+
+    #[test]
+    fn det_inverse() {
+        let m2 = [[4., 7.], [2., 6.]].map(|r| r.map(Fast));
+        assert_eq!(det2(&m2), Fast(10.));
+        assert_identity(&mul(&m2, &inverse2(&m2).unwrap()));
+
+        let m3 = [[2., -1., 0.], [-1., 2., -1.], [0., -1., 2.]].map(|r| r.map(Fast));
+        assert_eq!(det3(&m3), Fast(4.));
+        assert_identity(&mul(&inverse3(&m3).unwrap(), &m3));
+
+        let m4 = [[1f64, 2., 0., -1.],
+                  [3., 0., 2., 1.],
+                  [0., 1., 4., 2.],
+                  [2., -1., 1., 3.]].map(|r| r.map(Fast));
+        assert!((det4(&m4).get() + 54.).abs() < 1e-12);
+        assert_identity(&mul(&m4, &inverse4(&m4).unwrap()));
+        assert_identity(&mul(&inverse4(&m4).unwrap(), &m4));
+        assert_eq!(transpose(&transpose(&m4)), m4);
+
+        let singular = [[1., 2.], [2., 4.]].map(|r| r.map(Fast));
+        assert_eq!(inverse2(&singular), None);
+        assert_eq!(inverse3(&[[Fast(1f32); 3]; 3]), None);
+    }
+}
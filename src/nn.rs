@@ -0,0 +1,61 @@
+//! Loss kernels for training loops.
+//!
+//! If the two slices have different lengths, the extra elements of the
+//! longer one are ignored.
+
+use std::cmp::min;
+
+use FastFloat;
+use similarity::euclidean_squared;
+
+/// Return the cross-entropy `-Σ targets[i] · ln(probs[i])` of the predicted
+/// distribution `probs` against `targets`.
+///
+/// Probabilities are clamped from below to machine epsilon before taking the
+/// logarithm, so that a zero probability gives a large finite loss instead
+/// of infinity.
+///
+/// ```
+/// use fast_floats::Fast;
+/// use fast_floats::nn::cross_entropy;
+///
+/// let probs = [0.25, 0.5, 0.25].map(Fast);
+/// let targets = [0., 1., 0.].map(Fast);
+/// assert!((cross_entropy(&probs, &targets).get() - 2f64.ln()).abs() < 1e-12);
+/// ```
+pub fn cross_entropy<F: FastFloat>(probs: &[F], targets: &[F]) -> F {
+    let n = min(probs.len(), targets.len());
+    let eps = F::epsilon();
+    let mut acc = F::zero();
+    for (&p, &t) in probs[..n].iter().zip(&targets[..n]) {
+        acc += t * p.max(eps).ln();
+    }
+    -acc
+}
+
+/// Return the mean squared error between `a` and `b`, or zero if they are
+/// empty.
+pub fn mse<F: FastFloat>(a: &[F], b: &[F]) -> F {
+    let n = min(a.len(), b.len());
+    if n == 0 {
+        F::zero()
+    } else {
+        euclidean_squared(a, b) / F::from_usize(n)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use Fast;
+
+    #[test]
+    fn losses() {
+        let probs = [0., 1., 0.].map(Fast);
+        assert_eq!(cross_entropy(&probs, &[0., 1., 0.].map(Fast)), Fast(0.));
+        let large = cross_entropy(&probs, &[1., 0., 0.].map(Fast));
+        assert!(large.get() > 30. && large.get() < 40.);
+        assert_eq!(mse(&[1., 2., 3.].map(Fast), &[1., 4., 7.].map(Fast)), Fast(20. / 3.));
+        assert_eq!(mse::<Fast<f32>>(&[], &[]), Fast(0.));
+    }
+}
@@ -0,0 +1,140 @@
+//! Gradient noise for procedural generation.
+//!
+//! This is Ken Perlin's improved noise, with his reference permutation table
+//! and gradient sets, computed in `Fast<f32>` arithmetic. Noise is zero at
+//! integer coordinates and roughly in the range `-1` to `1`, and repeats
+//! with a period of 256 along each axis.
+
+use Fast;
+
+/// Ken Perlin's reference permutation of `0..256`
+const PERM: [u8; 256] = [
+    151, 160, 137,  91,  90,  15, 131,  13, 201,  95,  96,  53, 194, 233,   7, 225,
+    140,  36, 103,  30,  69, 142,   8,  99,  37, 240,  21,  10,  23, 190,   6, 148,
+    247, 120, 234,  75,   0,  26, 197,  62,  94, 252, 219, 203, 117,  35,  11,  32,
+     57, 177,  33,  88, 237, 149,  56,  87, 174,  20, 125, 136, 171, 168,  68, 175,
+     74, 165,  71, 134, 139,  48,  27, 166,  77, 146, 158, 231,  83, 111, 229, 122,
+     60, 211, 133, 230, 220, 105,  92,  41,  55,  46, 245,  40, 244, 102, 143,  54,
+     65,  25,  63, 161,   1, 216,  80,  73, 209,  76, 132, 187, 208,  89,  18, 169,
+    200, 196, 135, 130, 116, 188, 159,  86, 164, 100, 109, 198, 173, 186,   3,  64,
+     52, 217, 226, 250, 124, 123,   5, 202,  38, 147, 118, 126, 255,  82,  85, 212,
+    207, 206,  59, 227,  47,  16,  58,  17, 182, 189,  28,  42, 223, 183, 170, 213,
+    119, 248, 152,   2,  44, 154, 163,  70, 221, 153, 101, 155, 167,  43, 172,   9,
+    129,  22,  39, 253,  19,  98, 108, 110,  79, 113, 224, 232, 178, 185, 112, 104,
+    218, 246,  97, 228, 251,  34, 242, 193, 238, 210, 144,  12, 191, 179, 162, 241,
+     81,  51, 145, 235, 249,  14, 239, 107,  49, 192, 214,  31, 181, 199, 106, 157,
+    184,  84, 204, 176, 115, 121,  50,  45, 127,   4, 150, 254, 138, 236, 205,  93,
+    222, 114,  67,  29,  24,  72, 243, 141, 128, 195,  78,  66, 215,  61, 156, 180,
+];
+
+#[inline(always)]
+fn hash(i: usize) -> usize {
+    PERM[i & 255] as usize
+}
+
+/// Quintic fade curve `6t⁵ - 15t⁴ + 10t³`
+#[inline(always)]
+fn fade(t: Fast<f32>) -> Fast<f32> {
+    t * t * t * (t * (t * 6. - 15.) + 10.)
+}
+
+#[inline(always)]
+fn lerp(t: Fast<f32>, a: Fast<f32>, b: Fast<f32>) -> Fast<f32> {
+    a + t * (b - a)
+}
+
+/// Split `x` into its lattice cell (mod 256) and the offset within it
+#[inline(always)]
+fn cell(x: Fast<f32>) -> (usize, Fast<f32>) {
+    let floor = x.floor();
+    ((floor.get() as i32 & 255) as usize, x - floor)
+}
+
+#[inline(always)]
+fn grad2(hash: usize, x: Fast<f32>, y: Fast<f32>) -> Fast<f32> {
+    match hash & 7 {
+        0 => x + y,
+        1 => y - x,
+        2 => x - y,
+        3 => -x - y,
+        4 => x,
+        5 => -x,
+        6 => y,
+        _ => -y,
+    }
+}
+
+#[inline(always)]
+fn grad3(hash: usize, x: Fast<f32>, y: Fast<f32>, z: Fast<f32>) -> Fast<f32> {
+    let h = hash & 15;
+    let u = if h < 8 { x } else { y };
+    let v = if h < 4 { y } else if h == 12 || h == 14 { x } else { z };
+    (if h & 1 == 0 { u } else { -u }) + (if h & 2 == 0 { v } else { -v })
+}
+
+/// Return the 2D Perlin noise value at `(x, y)`.
+///
+/// ```
+/// use fast_floats::Fast;
+/// use fast_floats::noise::perlin2;
+///
+/// assert_eq!(perlin2(Fast(3.), Fast(-2.)), Fast(0.));
+/// let n = perlin2(Fast(3.5), Fast(-2.25));
+/// assert!(n.get() >= -1. && n.get() <= 1.);
+/// ```
+pub fn perlin2(x: Fast<f32>, y: Fast<f32>) -> Fast<f32> {
+    let (xi, x) = cell(x);
+    let (yi, y) = cell(y);
+    let (u, v) = (fade(x), fade(y));
+    let (a, b) = (hash(xi) + yi, hash(xi + 1) + yi);
+    let one = Fast(1.);
+    lerp(v,
+         lerp(u, grad2(hash(a), x, y), grad2(hash(b), x - one, y)),
+         lerp(u, grad2(hash(a + 1), x, y - one), grad2(hash(b + 1), x - one, y - one)))
+}
+
+/// Return the 3D Perlin noise value at `(x, y, z)`.
+pub fn perlin3(x: Fast<f32>, y: Fast<f32>, z: Fast<f32>) -> Fast<f32> {
+    let (xi, x) = cell(x);
+    let (yi, y) = cell(y);
+    let (zi, z) = cell(z);
+    let (u, v, w) = (fade(x), fade(y), fade(z));
+    let a = hash(xi) + yi;
+    let (aa, ab) = (hash(a) + zi, hash(a + 1) + zi);
+    let b = hash(xi + 1) + yi;
+    let (ba, bb) = (hash(b) + zi, hash(b + 1) + zi);
+    let one = Fast(1.);
+    let (x1, y1, z1) = (x - one, y - one, z - one);
+    lerp(w,
+         lerp(v,
+              lerp(u, grad3(hash(aa), x, y, z), grad3(hash(ba), x1, y, z)),
+              lerp(u, grad3(hash(ab), x, y1, z), grad3(hash(bb), x1, y1, z))),
+         lerp(v,
+              lerp(u, grad3(hash(aa + 1), x, y, z1), grad3(hash(ba + 1), x1, y, z1)),
+              lerp(u, grad3(hash(ab + 1), x, y1, z1), grad3(hash(bb + 1), x1, y1, z1))))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn perlin() {
+        let mut nonzero = 0;
+        for i in 0..200 {
+            let x = Fast(i as f32 * 0.37 - 30.);
+            let y = Fast(i as f32 * 0.11 + 5.);
+            let z = Fast(i as f32 * -0.23);
+            for &n in &[perlin2(x, y), perlin3(x, y, z)] {
+                assert!(n.get() >= -1.1 && n.get() <= 1.1);
+                nonzero += (n != Fast(0.)) as usize;
+            }
+            // continuous, and periodic with period 256
+            let d = Fast(1e-3);
+            assert!((perlin3(x + d, y, z) - perlin3(x, y, z)).abs() < Fast(1e-2));
+            assert!((perlin2(x + 256., y) - perlin2(x, y)).abs() < Fast(1e-3));
+        }
+        assert!(nonzero > 300);
+        assert_eq!(perlin3(Fast(1.), Fast(2.), Fast(3.)), Fast(0.));
+    }
+}
@@ -0,0 +1,82 @@
+//! Integration steps for particle systems.
+//!
+//! Each slice holds one component per particle (or the flattened components
+//! of all particles), and the updates are multiply-adds, which the compiler
+//! fuses when the target has FMA instructions. If the slices have different
+//! lengths, the extra elements of the longer ones are ignored.
+
+use FastFloat;
+
+/// Advance `pos` and `vel` by the time step `dt` with the explicit (forward)
+/// Euler method.
+///
+/// Positions are updated with the old velocities: `pos += vel · dt`, then
+/// `vel += acc · dt`.
+pub fn euler_step<F: FastFloat>(pos: &mut [F], vel: &mut [F], acc: &[F], dt: F) {
+    for ((x, v), &a) in pos.iter_mut().zip(vel).zip(acc) {
+        *x += *v * dt;
+        *v += a * dt;
+    }
+}
+
+/// Advance `pos` and `vel` by the time step `dt` with the semi-implicit
+/// (symplectic) Euler method.
+///
+/// Velocities are updated first, and positions with the new velocities:
+/// `vel += acc · dt`, then `pos += vel · dt`. Unlike the explicit method,
+/// this conserves energy well in oscillating systems.
+///
+/// ```
+/// use fast_floats::Fast;
+/// use fast_floats::physics::semi_implicit_euler;
+///
+/// let mut pos = [Fast(0.)];
+/// let mut vel = [Fast(1.)];
+/// semi_implicit_euler(&mut pos, &mut vel, &[Fast(-2.)], Fast(0.5));
+/// assert_eq!((pos[0], vel[0]), (Fast(0.), Fast(0.)));
+/// ```
+pub fn semi_implicit_euler<F: FastFloat>(pos: &mut [F], vel: &mut [F], acc: &[F], dt: F) {
+    for ((x, v), &a) in pos.iter_mut().zip(vel).zip(acc) {
+        *v += a * dt;
+        *x += *v * dt;
+    }
+}
+
+/// Advance `pos` by the time step `dt` with position Verlet integration,
+/// where `prev` holds the positions of the previous step.
+///
+/// The new position is `2 · pos - prev + acc · dt²`; afterwards `prev` holds
+/// the positions that were in `pos`. Velocities are implicit, and the
+/// first step can be taken from `prev = pos - vel · dt`.
+pub fn verlet_step<F: FastFloat>(pos: &mut [F], prev: &mut [F], acc: &[F], dt: F) {
+    let dt2 = dt * dt;
+    for ((x, p), &a) in pos.iter_mut().zip(prev).zip(acc) {
+        let next = a * dt2 + (*x + (*x - *p));
+        *p = *x;
+        *x = next;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use Fast;
+
+    #[test]
+    fn steps() {
+        let (mut pos, mut vel) = ([Fast(1.), Fast(2.)], [Fast(0.5), Fast(-1.)]);
+        let acc = [Fast(2.), Fast(4.)];
+        euler_step(&mut pos, &mut vel, &acc, Fast(0.5));
+        assert_eq!(pos, [Fast(1.25), Fast(1.5)]);
+        assert_eq!(vel, [Fast(1.5), Fast(1.)]);
+        semi_implicit_euler(&mut pos, &mut vel, &acc, Fast(0.5));
+        assert_eq!(vel, [Fast(2.5), Fast(3.)]);
+        assert_eq!(pos, [Fast(2.5), Fast(3.)]);
+
+        let (mut pos, mut prev) = ([Fast(0.)], [Fast(0.)]);
+        for _ in 0..4 {
+            verlet_step(&mut pos, &mut prev, &[Fast(2.)], Fast(0.5));
+        }
+        assert_eq!((pos[0], prev[0]), (Fast(5.), Fast(3.)));
+    }
+}
@@ -0,0 +1,87 @@
+//! Polynomial evaluation.
+//!
+//! Coefficients are given in order of increasing degree, so that
+//! `[c0, c1, c2]` is the polynomial `c0 + c1·x + c2·x²`.
+//!
+//! The multiply-adds are plain fast operations, which the compiler fuses
+//! when the target has FMA instructions; the fused multiply-add intrinsic
+//! would be a call to the math library otherwise.
+
+use FastFloat;
+
+/// Evaluate the polynomial with coefficients `coeffs` at `x` using Horner's
+/// scheme.
+///
+/// This uses the least number of operations, but each step depends on the
+/// previous one.
+///
+/// ```
+/// use fast_floats::Fast;
+/// use fast_floats::poly::eval_horner;
+///
+/// let p = [1., -3., 2.].map(Fast);
+/// assert_eq!(eval_horner(&p, Fast(2.)), Fast(3.));
+/// ```
+#[inline]
+pub fn eval_horner<F: FastFloat>(coeffs: &[F], x: F) -> F {
+    coeffs.iter().rev().fold(F::zero(), |acc, &c| acc * x + c)
+}
+
+/// Evaluate the polynomial with coefficients `coeffs` at `x` using Estrin's
+/// scheme.
+///
+/// Coefficients are combined pairwise in a balanced tree of multiply-adds,
+/// with powers `x, x², x⁴, …`, so that the independent operations of each
+/// level can execute in parallel. The number of coefficients `N` is a
+/// constant, so the tree is fully unrolled at compile time.
+///
+/// ```
+/// use fast_floats::Fast;
+/// use fast_floats::poly::eval_estrin;
+///
+/// let p = [1., -3., 2.].map(Fast);
+/// assert_eq!(eval_estrin(&p, Fast(2.)), Fast(3.));
+/// ```
+#[inline]
+pub fn eval_estrin<F: FastFloat, const N: usize>(coeffs: &[F; N], x: F) -> F {
+    if N == 0 {
+        return F::zero();
+    }
+    let mut c = *coeffs;
+    let mut n = N;
+    let mut p = x;
+    // constant trip counts, so that both loops are fully unrolled
+    let levels = usize::BITS - (N - 1).leading_zeros();
+    for _ in 0..levels {
+        for i in 0..N.div_ceil(2) {
+            if 2 * i + 1 < n {
+                c[i] = c[2 * i + 1] * p + c[2 * i];
+            } else if 2 * i < n {
+                c[i] = c[2 * i];
+            }
+        }
+        n = n.div_ceil(2);
+        p = p * p;
+    }
+    c[0]
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use Fast;
+
+    #[test]
+    fn horner_estrin() {
+        let p = [3., -1., 4., 1., -5., 9., 2.].map(Fast);
+        for &x in &[-2., -0.5, 0., 1., 3.] {
+            let x = Fast(x);
+            let naive = p.iter().rev().fold(Fast(0.), |acc, &c| acc * x + c);
+            assert_eq!(eval_horner(&p, x), naive);
+            assert_eq!(eval_estrin(&p, x), naive);
+            assert_eq!(eval_estrin(&[p[0], p[1], p[2], p[3]], x), eval_horner(&p[..4], x));
+        }
+        assert_eq!(eval_estrin(&[], Fast(1.)), Fast(0.));
+        assert_eq!(eval_estrin(&[Fast(7.)], Fast(1.)), Fast(7.));
+    }
+}
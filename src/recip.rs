@@ -0,0 +1,207 @@
+//! Approximate division using a hardware reciprocal estimate.
+//!
+//! On x86 (with SSE) and x86-64, `Fast<f32>` division can be replaced by
+//! the `rcpss` reciprocal estimate (`rcpps` for four lanes), refined with one
+//! Newton step, and a multiplication; on AArch64 by `frecpe` the same way. This has more
+//! throughput than a full division, for loops that are bound by it. The
+//! relative error is below about `2.5e-7` on x86 and `1.6e-5` on AArch64.
+//!
+//! On other targets, and for `Fast<f64>` which has no estimate instruction
+//! in the baseline instruction sets, the approximate operations are exact
+//! fast divisions.
+
+use std::cmp::min;
+
+use FastFloat;
+use Fast;
+
+/// Return an estimate of `1 / x`.
+#[cfg(any(all(target_arch = "x86", target_feature = "sse"), target_arch = "x86_64"))]
+#[inline(always)]
+fn recip_estimate(x: f32) -> f32 {
+    #[cfg(target_arch = "x86")]
+    use std::arch::x86::{_mm_cvtss_f32, _mm_rcp_ss, _mm_set_ss};
+    #[cfg(target_arch = "x86_64")]
+    use std::arch::x86_64::{_mm_cvtss_f32, _mm_rcp_ss, _mm_set_ss};
+    unsafe { _mm_cvtss_f32(_mm_rcp_ss(_mm_set_ss(x))) }
+}
+
+/// Return estimates of `1 / x` for each lane of `x`.
+#[cfg(any(all(target_arch = "x86", target_feature = "sse"), target_arch = "x86_64"))]
+#[inline(always)]
+fn recip_estimate4(x: [f32; 4]) -> [f32; 4] {
+    #[cfg(target_arch = "x86")]
+    use std::arch::x86::{_mm_loadu_ps, _mm_rcp_ps, _mm_storeu_ps};
+    #[cfg(target_arch = "x86_64")]
+    use std::arch::x86_64::{_mm_loadu_ps, _mm_rcp_ps, _mm_storeu_ps};
+    let mut r = [0.; 4];
+    unsafe { _mm_storeu_ps(r.as_mut_ptr(), _mm_rcp_ps(_mm_loadu_ps(x.as_ptr()))) };
+    r
+}
+
+/// Return an estimate of `1 / x`.
+#[cfg(target_arch = "aarch64")]
+#[inline(always)]
+fn recip_estimate(x: f32) -> f32 {
+    unsafe { std::arch::aarch64::vrecpes_f32(x) }
+}
+
+/// Return estimates of `1 / x` for each lane of `x`.
+#[cfg(target_arch = "aarch64")]
+#[inline(always)]
+fn recip_estimate4(x: [f32; 4]) -> [f32; 4] {
+    use std::arch::aarch64::{vld1q_f32, vrecpeq_f32, vst1q_f32};
+    let mut r = [0.; 4];
+    unsafe { vst1q_f32(r.as_mut_ptr(), vrecpeq_f32(vld1q_f32(x.as_ptr()))) };
+    r
+}
+
+/// Return an estimate of `1 / x`.
+#[cfg(not(any(all(target_arch = "x86", target_feature = "sse"),
+              target_arch = "x86_64",
+              target_arch = "aarch64")))]
+#[inline(always)]
+fn recip_estimate(x: f32) -> f32 {
+    1. / x
+}
+
+/// Return estimates of `1 / x` for each lane of `x`.
+#[cfg(not(any(all(target_arch = "x86", target_feature = "sse"),
+              target_arch = "x86_64",
+              target_arch = "aarch64")))]
+#[inline(always)]
+fn recip_estimate4(x: [f32; 4]) -> [f32; 4] {
+    x.map(recip_estimate)
+}
+
+/// Refine the estimate `r` of `1 / x` with one Newton step.
+#[inline(always)]
+fn newton_step(x: Fast<f32>, r: Fast<f32>) -> Fast<f32> {
+    r * (Fast(2.) - x * r)
+}
+
+impl Fast<f32> {
+    /// Return an approximation of `1 / self`, a reciprocal estimate refined
+    /// with one Newton step.
+    ///
+    /// `self` must be a finite, normal float. See the
+    /// [module documentation](recip/index.html) for the error bounds.
+    #[inline(always)]
+    pub fn recip_approx(self) -> Self {
+        newton_step(self, Fast(recip_estimate(self.0)))
+    }
+
+    /// Return an approximation of `self / rhs`, computed as `self` times an
+    /// approximation of `1 / rhs`.
+    ///
+    /// `rhs` must be a finite, normal float. See the
+    /// [module documentation](recip/index.html) for the error bounds.
+    ///
+    /// ```
+    /// use fast_floats::Fast;
+    ///
+    /// let q = Fast(1f32).div_approx(Fast(3.));
+    /// assert!((q.get() - 1. / 3.).abs() < 1e-5);
+    /// ```
+    #[inline(always)]
+    pub fn div_approx(self, rhs: Self) -> Self {
+        self * rhs.recip_approx()
+    }
+
+    /// Return the lane-wise `div_approx` of `x` and `y`, with the packed
+    /// reciprocal estimate.
+    #[inline(always)]
+    pub fn div_approx4(x: [Self; 4], y: [Self; 4]) -> [Self; 4] {
+        let r = recip_estimate4(y.map(Fast::get));
+        let mut q = x;
+        for j in 0..4 {
+            q[j] = x[j] * newton_step(y[j], Fast(r[j]));
+        }
+        q
+    }
+}
+
+impl Fast<f64> {
+    /// Return `self / rhs`.
+    ///
+    /// There is no fast reciprocal estimate for `f64`, so this is the same
+    /// as division; it exists so that code generic over the width can use
+    /// the approximate division.
+    #[inline(always)]
+    pub fn div_approx(self, rhs: Self) -> Self {
+        self / rhs
+    }
+
+    /// Return the lane-wise quotient of `x` and `y`.
+    #[inline(always)]
+    pub fn div_approx4(x: [Self; 4], y: [Self; 4]) -> [Self; 4] {
+        let mut q = x;
+        for j in 0..4 {
+            q[j] = x[j] / y[j];
+        }
+        q
+    }
+}
+
+/// Divide each of `xs` by the corresponding element of `ys`, with
+/// `div_approx`.
+///
+/// If the slices have different lengths, the extra elements of the longer
+/// one are ignored.
+///
+/// ```
+/// use fast_floats::Fast;
+/// use fast_floats::recip::div_in_place_approx;
+///
+/// let mut xs = [1f32, 2., 3.].map(Fast);
+/// div_in_place_approx(&mut xs, &[4f32, 8., 6.].map(Fast));
+/// for (x, y) in xs.iter().zip(&[0.25, 0.25, 0.5]) {
+///     assert!((x.get() - y).abs() < 1e-5);
+/// }
+/// ```
+pub fn div_in_place_approx<F: FastFloat>(xs: &mut [F], ys: &[F]) {
+    let n = min(xs.len(), ys.len());
+    let (xs, ys) = (&mut xs[..n], &ys[..n]);
+    let mut xc = xs.chunks_exact_mut(4);
+    let yc = ys.chunks_exact(4);
+    let yt = yc.remainder();
+    for (x, y) in (&mut xc).zip(yc) {
+        let q = F::div_approx4([x[0], x[1], x[2], x[3]], [y[0], y[1], y[2], y[3]]);
+        x.copy_from_slice(&q);
+    }
+    for (x, &y) in xc.into_remainder().iter_mut().zip(yt) {
+        *x = x.div_approx(y);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn approximate_division() {
+        let mut worst = 0f32;
+        let mut x = 1e-30f32;
+        while x < 1e30 {
+            for &y in &[x, -x * 1.37] {
+                let q = Fast(y).recip_approx().get();
+                worst = worst.max((q * y - 1.).abs());
+            }
+            x *= 1.013;
+        }
+        assert!(worst < 1.6e-5);
+        #[cfg(any(target_arch = "x86", target_arch = "x86_64"))]
+        assert!(worst < 2.5e-7);
+
+        let mut xs = [1., 2., 3.].map(Fast);
+        div_in_place_approx(&mut xs, &[4., 8., 12.].map(Fast));
+        assert_eq!(xs, [0.25f64; 3].map(Fast));
+
+        let mut xs = [1f32; 7].map(Fast);
+        let ys = [3f32, -3., 7., 1e-30, 1e30, 0.5, 3.].map(Fast);
+        div_in_place_approx(&mut xs, &ys);
+        for (x, y) in xs.iter().zip(&ys) {
+            assert!((x.get() * y.get() - 1.).abs() < 1.6e-5);
+        }
+    }
+}
@@ -0,0 +1,325 @@
+//! Reduction kernels: sums and dot products.
+//!
+//! The kernels keep several independent accumulators, which together with
+//! the fast-math flags lets the compiler reorder and vectorize the loops.
+//! The `_unrolled` variants take the number of accumulators as a const
+//! generic, to tune it for a particular target.
+
+use std::cmp::min;
+
+use FastFloat;
+
+/// Number of independent accumulators used by the kernels.
+pub(crate) const ACCS: usize = 4;
+
+/// Add up the accumulators pairwise.
+#[inline(always)]
+pub(crate) fn merge<F: FastFloat, const K: usize>(mut acc: [F; K]) -> F {
+    let mut n = K;
+    while n > 1 {
+        let half = n / 2;
+        for j in 0..half {
+            acc[j] += acc[n - half + j];
+        }
+        n -= half;
+    }
+    acc[0]
+}
+
+/// Return the sum of `xs`.
+pub fn sum<F: FastFloat>(xs: &[F]) -> F {
+    sum_unrolled::<ACCS, F>(xs)
+}
+
+/// Return the sum of `xs`, using `K` independent accumulators.
+///
+/// ```
+/// use fast_floats::Fast;
+/// use fast_floats::reduce::sum_unrolled;
+///
+/// let xs = [1., 2., 3., 4., 5., 6., 7., 8., 9., 10.].map(Fast);
+/// assert_eq!(sum_unrolled::<8, _>(&xs), Fast(55.));
+/// ```
+pub fn sum_unrolled<const K: usize, F: FastFloat>(xs: &[F]) -> F {
+    const { assert!(K > 0, "at least one accumulator is needed") };
+    let mut acc = [F::zero(); K];
+    let chunks = xs.chunks_exact(K);
+    let tail = chunks.remainder();
+    for c in chunks {
+        for j in 0..K {
+            acc[j] += c[j];
+        }
+    }
+    for (a, &x) in acc.iter_mut().zip(tail) {
+        *a += x;
+    }
+    merge(acc)
+}
+
+/// Return the dot product of `xs` and `ys`.
+///
+/// If the slices have different lengths, the extra elements of the longer
+/// one are ignored.
+pub fn dot<F: FastFloat>(xs: &[F], ys: &[F]) -> F {
+    dot_unrolled::<ACCS, F>(xs, ys)
+}
+
+/// Return the dot product of `xs` and `ys`, using `K` independent
+/// accumulators.
+///
+/// If the slices have different lengths, the extra elements of the longer
+/// one are ignored.
+pub fn dot_unrolled<const K: usize, F: FastFloat>(xs: &[F], ys: &[F]) -> F {
+    const { assert!(K > 0, "at least one accumulator is needed") };
+    let n = min(xs.len(), ys.len());
+    let (xs, ys) = (&xs[..n], &ys[..n]);
+    let mut acc = [F::zero(); K];
+    let xc = xs.chunks_exact(K);
+    let yc = ys.chunks_exact(K);
+    let (xt, yt) = (xc.remainder(), yc.remainder());
+    for (x, y) in xc.zip(yc) {
+        for j in 0..K {
+            acc[j] += x[j] * y[j];
+        }
+    }
+    for ((a, &x), &y) in acc.iter_mut().zip(xt).zip(yt) {
+        *a += x * y;
+    }
+    merge(acc)
+}
+
+/// Return the dot product of every `stride_x`th element of `xs` with every
+/// `stride_y`th element of `ys`, starting from the first element of each.
+///
+/// This is for example the dot product of two columns in row-major matrices.
+/// The number of terms is the smaller of the two strided lengths.
+///
+/// ***Panics*** if a stride is zero.
+///
+/// ```
+/// use fast_floats::Fast;
+/// use fast_floats::reduce::dot_strided;
+///
+/// // second column of a 3 x 2 matrix, with itself
+/// let a = [1., 2., 3., 4., 5., 6.].map(Fast);
+/// assert_eq!(dot_strided(&a[1..], 2, &a[1..], 2), Fast(56.));
+/// ```
+pub fn dot_strided<F: FastFloat>(xs: &[F], stride_x: usize, ys: &[F], stride_y: usize) -> F {
+    assert!(stride_x != 0 && stride_y != 0, "dot_strided: zero stride");
+    let n = min(xs.len().div_ceil(stride_x), ys.len().div_ceil(stride_y));
+    let mut acc = [F::zero(); ACCS];
+    let mut i = 0;
+    while i + ACCS <= n {
+        for j in 0..ACCS {
+            acc[j] += xs[(i + j) * stride_x] * ys[(i + j) * stride_y];
+        }
+        i += ACCS;
+    }
+    for (a, i) in acc.iter_mut().zip(i..n) {
+        *a += xs[i * stride_x] * ys[i * stride_y];
+    }
+    merge(acc)
+}
+
+/// Return the dot product of a sparse vector, given as its nonzero `values`
+/// and their `indices`, with the dense vector `dense`.
+///
+/// That is, the sum of `values[i] * dense[indices[i]]`. If `values` and
+/// `indices` have different lengths, the extra elements are ignored.
+///
+/// ***Panics*** if an index is out of bounds for `dense`.
+pub fn dot_gather<F: FastFloat>(values: &[F], indices: &[usize], dense: &[F]) -> F {
+    let n = min(values.len(), indices.len());
+    let (values, indices) = (&values[..n], &indices[..n]);
+    let mut acc = [F::zero(); ACCS];
+    let vc = values.chunks_exact(ACCS);
+    let ic = indices.chunks_exact(ACCS);
+    let (vt, it) = (vc.remainder(), ic.remainder());
+    for (v, ix) in vc.zip(ic) {
+        for j in 0..ACCS {
+            acc[j] += v[j] * dense[ix[j]];
+        }
+    }
+    for ((a, &v), &ix) in acc.iter_mut().zip(vt).zip(it) {
+        *a += v * dense[ix];
+    }
+    merge(acc)
+}
+
+#[inline(always)]
+fn mask_value<F: FastFloat>(m: bool) -> F {
+    F::from_usize(m as usize)
+}
+
+/// Return the sum of the elements of `values` where `mask` is `true`.
+///
+/// Masked out elements are multiplied by zero instead of skipped, so that
+/// the loop stays branchless; they must still be finite. If the slices have
+/// different lengths, the extra elements of the longer one are ignored.
+///
+/// ```
+/// use fast_floats::Fast;
+/// use fast_floats::reduce::sum_where;
+///
+/// let xs = [1., 2., 3., 4.].map(Fast);
+/// let mask = xs.map(|x| x > Fast(1.5));
+/// assert_eq!(sum_where(&xs, &mask), Fast(9.));
+/// ```
+pub fn sum_where<F: FastFloat>(values: &[F], mask: &[bool]) -> F {
+    let n = min(values.len(), mask.len());
+    let (values, mask) = (&values[..n], &mask[..n]);
+    let mut acc = [F::zero(); ACCS];
+    let vc = values.chunks_exact(ACCS);
+    let mc = mask.chunks_exact(ACCS);
+    let (vt, mt) = (vc.remainder(), mc.remainder());
+    for (v, m) in vc.zip(mc) {
+        for j in 0..ACCS {
+            acc[j] += v[j] * mask_value(m[j]);
+        }
+    }
+    for ((a, &v), &m) in acc.iter_mut().zip(vt).zip(mt) {
+        *a += v * mask_value(m);
+    }
+    merge(acc)
+}
+
+/// Return the dot product of `xs` and `ys`, counting only the terms where
+/// `mask` is `true`.
+///
+/// Like [`sum_where`](fn.sum_where.html), this is branchless, and masked out
+/// elements must still be finite.
+pub fn dot_where<F: FastFloat>(xs: &[F], ys: &[F], mask: &[bool]) -> F {
+    let n = min(min(xs.len(), ys.len()), mask.len());
+    let (xs, ys, mask) = (&xs[..n], &ys[..n], &mask[..n]);
+    let mut acc = [F::zero(); ACCS];
+    let xc = xs.chunks_exact(ACCS);
+    let yc = ys.chunks_exact(ACCS);
+    let mc = mask.chunks_exact(ACCS);
+    let (xt, yt, mt) = (xc.remainder(), yc.remainder(), mc.remainder());
+    for ((x, y), m) in xc.zip(yc).zip(mc) {
+        for j in 0..ACCS {
+            acc[j] += x[j] * y[j] * mask_value(m[j]);
+        }
+    }
+    for (((a, &x), &y), &m) in acc.iter_mut().zip(xt).zip(yt).zip(mt) {
+        *a += x * y * mask_value(m);
+    }
+    merge(acc)
+}
+
+/// A streaming dot product of two sequences, fed one pair at a time.
+///
+/// The products are buffered, and each full chunk of them is added lane by
+/// lane to independent partial sums, so that the additions of consecutive
+/// `push` calls don't form one dependency chain.
+///
+/// ```
+/// use fast_floats::Fast;
+/// use fast_floats::reduce::RunningDot;
+///
+/// let mut dot = RunningDot::new();
+/// for i in 0..10 {
+///     dot.push(Fast(i as f64), Fast(2.));
+/// }
+/// assert_eq!(dot.value(), Fast(90.));
+/// ```
+#[derive(Copy, Clone, Debug)]
+pub struct RunningDot<F> {
+    acc: [F; ACCS],
+    pending: [F; ACCS],
+    len: usize,
+}
+
+impl<F: FastFloat> RunningDot<F> {
+    /// Create a new accumulator with the value zero.
+    #[inline]
+    pub fn new() -> Self {
+        RunningDot {
+            acc: [F::zero(); ACCS],
+            pending: [F::zero(); ACCS],
+            len: 0,
+        }
+    }
+
+    /// Add the product `x * y`.
+    #[inline(always)]
+    pub fn push(&mut self, x: F, y: F) {
+        self.pending[self.len] = x * y;
+        self.len += 1;
+        if self.len == ACCS {
+            for j in 0..ACCS {
+                self.acc[j] += self.pending[j];
+            }
+            self.len = 0;
+        }
+    }
+
+    /// Return the dot product of the pairs pushed so far.
+    #[inline]
+    pub fn value(&self) -> F {
+        let mut acc = self.acc;
+        for (a, &p) in acc.iter_mut().zip(&self.pending[..self.len]) {
+            *a += p;
+        }
+        merge(acc)
+    }
+}
+
+impl<F: FastFloat> Default for RunningDot<F> {
+    #[inline]
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use Fast;
+
+    const XS: [Fast<f64>; 7] = [Fast(1.), Fast(2.), Fast(3.), Fast(4.), Fast(5.), Fast(6.), Fast(7.)];
+
+    #[test]
+    fn sums() {
+        assert_eq!(sum(&XS), Fast(28.));
+        assert_eq!(sum::<Fast<f32>>(&[]), Fast(0.));
+        assert_eq!(sum_unrolled::<1, _>(&XS), Fast(28.));
+        assert_eq!(sum_unrolled::<3, _>(&XS), Fast(28.));
+    }
+
+    #[test]
+    fn dots() {
+        assert_eq!(dot(&XS, &XS[..6]), Fast(91.));
+        assert_eq!(dot_unrolled::<5, _>(&XS, &XS[..6]), Fast(91.));
+        assert_eq!(dot_unrolled::<2, _>(&XS[..1], &XS), Fast(1.));
+    }
+
+    #[test]
+    fn strided() {
+        assert_eq!(dot_strided(&XS, 3, &XS, 1), Fast(1. * 1. + 4. * 2. + 7. * 3.));
+        assert_eq!(dot_strided(&XS, 1, &XS, 1), dot(&XS, &XS));
+    }
+
+    #[test]
+    fn gather() {
+        assert_eq!(dot_gather(&XS[..5], &[6, 0, 1, 1, 2], &XS), Fast(7. + 2. + 6. + 8. + 15.));
+    }
+
+    #[test]
+    fn masked() {
+        let odd = XS.map(|x| x.get() as i32 % 2 == 1);
+        assert_eq!(sum_where(&XS, &odd), Fast(16.));
+        assert_eq!(dot_where(&XS, &XS, &odd), Fast(1. + 9. + 25. + 49.));
+        assert_eq!(sum_where(&XS, &[false; 7]), Fast(0.));
+    }
+
+    #[test]
+    fn running_dot() {
+        let mut running = RunningDot::default();
+        assert_eq!(running.value(), Fast(0.));
+        for (i, &x) in XS.iter().enumerate() {
+            running.push(x, x);
+            assert_eq!(running.value(), dot(&XS[..i + 1], &XS[..i + 1]));
+        }
+    }
+}
@@ -0,0 +1,124 @@
+//! A small, fast pseudorandom number generator that produces `Fast` values.
+//!
+//! This is available without the `rand` dependency, for example for Monte
+//! Carlo simulation and dithering in embedded code. It is not suitable for
+//! cryptography.
+
+use Fast;
+
+/// The xoshiro256++ generator by Blackman and Vigna.
+///
+/// ```
+/// use fast_floats::rng::Xoshiro256;
+///
+/// let mut rng = Xoshiro256::seed_from_u64(7);
+/// let x = rng.next_f64();
+/// assert!(x.get() >= 0. && x.get() < 1.);
+/// ```
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Xoshiro256 {
+    s: [u64; 4],
+}
+
+impl Xoshiro256 {
+    /// Create a generator from a 64-bit seed, which is expanded to the full
+    /// state with SplitMix64. Every seed, including zero, gives a valid state.
+    pub fn seed_from_u64(seed: u64) -> Self {
+        let mut z = seed;
+        let mut s = [0; 4];
+        for x in &mut s {
+            z = z.wrapping_add(0x9e3779b97f4a7c15);
+            let mut t = z;
+            t = (t ^ (t >> 30)).wrapping_mul(0xbf58476d1ce4e5b9);
+            t = (t ^ (t >> 27)).wrapping_mul(0x94d049bb133111eb);
+            *x = t ^ (t >> 31);
+        }
+        Xoshiro256 { s }
+    }
+
+    /// Return the next 64 random bits.
+    #[inline]
+    pub fn next_u64(&mut self) -> u64 {
+        let s = &mut self.s;
+        let result = s[0].wrapping_add(s[3]).rotate_left(23).wrapping_add(s[0]);
+        let t = s[1] << 17;
+        s[2] ^= s[0];
+        s[3] ^= s[1];
+        s[1] ^= s[2];
+        s[0] ^= s[3];
+        s[2] ^= t;
+        s[3] = s[3].rotate_left(45);
+        result
+    }
+
+    /// Return a uniformly distributed `f32` in `[0, 1)`.
+    #[inline]
+    pub fn next_f32(&mut self) -> Fast<f32> {
+        Fast((self.next_u64() >> 40) as f32 * (1. / (1u32 << 24) as f32))
+    }
+
+    /// Return a uniformly distributed `f64` in `[0, 1)`.
+    #[inline]
+    pub fn next_f64(&mut self) -> Fast<f64> {
+        Fast((self.next_u64() >> 11) as f64 * (1. / (1u64 << 53) as f64))
+    }
+}
+
+/// Requires crate feature `"rand"`.
+#[cfg(feature = "rand")]
+impl ::rand::RngCore for Xoshiro256 {
+    #[inline]
+    fn next_u32(&mut self) -> u32 {
+        (self.next_u64() >> 32) as u32
+    }
+
+    #[inline]
+    fn next_u64(&mut self) -> u64 {
+        Xoshiro256::next_u64(self)
+    }
+
+    fn fill_bytes(&mut self, dest: &mut [u8]) {
+        for chunk in dest.chunks_mut(8) {
+            let bytes = Xoshiro256::next_u64(self).to_le_bytes();
+            chunk.copy_from_slice(&bytes[..chunk.len()]);
+        }
+    }
+
+    fn try_fill_bytes(&mut self, dest: &mut [u8]) -> Result<(), ::rand::Error> {
+        self.fill_bytes(dest);
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn uniforms() {
+        let mut rng = Xoshiro256::seed_from_u64(0);
+        let mut sum = Fast(0.);
+        for _ in 0..10_000 {
+            let (x, y) = (rng.next_f64(), rng.next_f32());
+            assert!(x >= Fast(0.) && x < Fast(1.));
+            assert!(y >= Fast(0.) && y < Fast(1.));
+            sum += x;
+        }
+        assert!((sum.get() / 10_000. - 0.5).abs() < 0.02);
+        let mut other = Xoshiro256::seed_from_u64(1);
+        assert!(rng.next_u64() != other.next_u64());
+        assert_eq!(rng.clone().next_u64(), rng.next_u64());
+    }
+
+    #[test]
+    fn known_answers() {
+        // outputs of the reference SplitMix64 and xoshiro256++ in C
+        let rng = Xoshiro256::seed_from_u64(0);
+        assert_eq!(rng.s, [0xe220a8397b1dcdaf, 0x6e789e6aa1b965f4,
+                           0x06c45d188009454f, 0xf88bb8a8724c81ec]);
+        let mut rng = Xoshiro256 { s: [1, 2, 3, 4] };
+        let xs: [u64; 6] = std::array::from_fn(|_| rng.next_u64());
+        assert_eq!(xs, [41943041, 58720359, 3588806011781223, 3591011842654386,
+                        9228616714210784205, 9973669472204895162]);
+    }
+}
@@ -0,0 +1,189 @@
+//! Rounding helpers.
+
+#[cfg(feature = "rand")]
+use rand::Rng;
+
+use FastFloat;
+use Fast;
+
+/// Return `[1, 10, 100, ...]`, which are exact while `10^k` has no more
+/// significant bits than the float type.
+const fn powers_of_ten<const N: usize>() -> [f64; N] {
+    let mut p = [1.; N];
+    let mut k = 1;
+    while k < N {
+        p[k] = p[k - 1] * 10.;
+        k += 1;
+    }
+    p
+}
+
+const POW10_F32: [f64; 11] = powers_of_ten();
+const POW10_F64: [f64; 23] = powers_of_ten();
+
+macro_rules! impl_round_to {
+    ($($f:ident $pow10:ident)+) => {
+        $(
+        impl Fast<$f> {
+            /// Round to `ndigits` decimal digits after the decimal point,
+            /// with halfway cases away from zero; a negative `ndigits`
+            /// rounds to tens, hundreds and so on.
+            ///
+            /// `self` is scaled by a power of ten, rounded and scaled back,
+            /// so the result is the float nearest to the rounded decimal,
+            /// but the scaling can move a value that is very close to a
+            /// halfway case to either side of it. Beyond the range of
+            /// exactly representable powers of ten, `±10` for `f32` and
+            /// `±22` for `f64`, the scaling takes several steps, each of
+            /// which can round.
+            ///
+            /// ```
+            /// use fast_floats::Fast;
+            ///
+            /// assert_eq!(Fast(3.14159f64).round_to(2), Fast(3.14));
+            /// assert_eq!(Fast(1234.5f32).round_to(-2), Fast(1200.));
+            /// ```
+            #[inline]
+            pub fn round_to(self, ndigits: i32) -> Self {
+                let max = $pow10.len() as i32 - 1;
+                let p_max = Fast($pow10[max as usize] as $f);
+                // large values are integers already, and would overflow
+                let big = Fast((1u64 << ($f::MANTISSA_DIGITS - 1)) as $f);
+                // beyond this, the result is zero or `self` anyway
+                let bound = 2 * $f::MAX_10_EXP;
+                let mut n = ndigits.max(-bound).min(bound);
+                // scale by the largest exact power of ten until the rest of
+                // the power is in the table; divisions use precise
+                // arithmetic, since fast-math may turn them into
+                // multiplications by an inexact reciprocal
+                let mut x = self;
+                let mut steps = 0;
+                while n > max {
+                    if x.abs() >= big {
+                        return self;
+                    }
+                    x *= p_max;
+                    n -= max;
+                    steps += 1;
+                }
+                while n < -max {
+                    x = Fast(x.0 / p_max.0);
+                    n += max;
+                    steps += 1;
+                }
+                let p = Fast($pow10[n.unsigned_abs() as usize] as $f);
+                let mut y = if n >= 0 {
+                    if x.abs() >= big {
+                        return self;
+                    }
+                    Fast((x * p).round().0 / p.0)
+                } else {
+                    Fast(x.0 / p.0).round() * p
+                };
+                for _ in 0..steps {
+                    if ndigits > 0 {
+                        y = Fast(y.0 / p_max.0);
+                    } else {
+                        y *= p_max;
+                    }
+                }
+                y
+            }
+        }
+        )+
+    }
+}
+
+impl_round_to!(f32 POW10_F32 f64 POW10_F64);
+
+/// Round each element of `xs` to `ndigits` decimal digits, as with
+/// `Fast::round_to`.
+pub fn round_to_in_place<F: FastFloat>(xs: &mut [F], ndigits: i32) {
+    for x in xs {
+        *x = x.round_to(ndigits);
+    }
+}
+
+#[cfg(feature = "rand")]
+macro_rules! impl_round_stochastic {
+    ($($f:ident)+) => {
+        $(
+        impl Fast<$f> {
+            /// Round to one of the two nearest integers at random, rounding
+            /// up with probability equal to the fractional part.
+            ///
+            /// The expected value of the result is `self`, so unlike
+            /// round-to-nearest, repeated rounding does not introduce bias.
+            ///
+            /// Requires crate feature `"rand"`.
+            #[inline]
+            pub fn round_stochastic<R: Rng + ?Sized>(self, rng: &mut R) -> Self {
+                let down = self.floor();
+                let u: $f = rng.gen();
+                if u < (self - down).0 {
+                    down + 1.
+                } else {
+                    down
+                }
+            }
+        }
+        )+
+    }
+}
+
+#[cfg(feature = "rand")]
+impl_round_stochastic!(f32 f64);
+
+/// Round each element of `xs` stochastically, as with
+/// `Fast::round_stochastic`.
+///
+/// Requires crate feature `"rand"`.
+#[cfg(feature = "rand")]
+pub fn round_stochastic_in_place<F: FastFloat, R: Rng + ?Sized>(xs: &mut [F], rng: &mut R) {
+    for x in xs {
+        *x = x.round_stochastic(rng);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    #[cfg(feature = "rand")]
+    use rand::rngs::mock::StepRng;
+
+    #[test]
+    fn decimal() {
+        let mut xs = [2.6749, -0.125, 1e300, 0.5e-22, 123.456].map(Fast);
+        round_to_in_place(&mut xs, 2);
+        assert_eq!(xs, [2.67, -0.13, 1e300, 0., 123.46].map(Fast));
+        assert_eq!(Fast(0.123_456_789_1f64).round_to(9), Fast(0.123_456_789));
+        assert_eq!(Fast(-1250f32).round_to(-2), Fast(-1300.));
+        assert_eq!(Fast(7e9f32).round_to(-40), Fast(0.));
+        assert_eq!(Fast(-7e10f32).round_to(-11), Fast(-1e11));
+        assert_eq!(Fast(0.1f32).round_to(40), Fast(0.1));
+        assert_eq!(Fast(1e-30f64).round_to(30), Fast(1e-30));
+        assert_eq!(Fast(1.234_567e-30f64).round_to(33), Fast(1.235e-30));
+        assert_eq!(Fast(1e30f32).round_to(-11), Fast(1e30));
+        assert_eq!(Fast(f64::MAX).round_to(i32::MIN), Fast(0.));
+        assert_eq!(Fast(5e-324f64).round_to(i32::MAX), Fast(5e-324));
+    }
+
+    #[cfg(feature = "rand")]
+    #[test]
+    fn stochastic() {
+        // uniforms 0, 0.25, 0.5, 0.75, ...
+        let mut rng = StepRng::new(0, 1 << 62);
+        let xs = [2.5f64; 4].map(|x| Fast(x).round_stochastic(&mut rng));
+        assert_eq!(xs, [3., 3., 2., 2.].map(Fast));
+        let mut xs = [Fast(-0.25); 4];
+        round_stochastic_in_place(&mut xs, &mut rng);
+        assert_eq!(xs, [0., 0., 0., -1.].map(Fast));
+
+        // the slice version draws the same numbers as the scalar one
+        let mut rng = StepRng::new(0, 1 << 62);
+        let ys = [0.5f32; 4].map(|x| Fast(x).round_stochastic(&mut rng));
+        let mut xs = [Fast(0.5f32); 4];
+        round_stochastic_in_place(&mut xs, &mut StepRng::new(0, 1 << 62));
+        assert_eq!(xs, ys);
+    }
+}
@@ -0,0 +1,493 @@
+//! Fixed width vectors of fast floats.
+//!
+//! `FastSimd<F, N>` holds `N` lanes of `Fast<F>`, and its operations work
+//! lane by lane with the fast-math scalar operations. The lane count is a
+//! constant, so the compiler unrolls the lane loops and can map them to
+//! vector instructions, without depending on unstable SIMD types.
+//!
+//! Slices rarely have a multiple of `N` elements. The masked loads and
+//! stores help with the remaining tail, and
+//! [`fold_chunks_with_tail`](fn.fold_chunks_with_tail.html) packages the
+//! whole pattern. [`as_simd`](fn.as_simd.html) splits a slice into an
+//! aligned body of vectors and the scalar elements around it.
+
+use std::mem;
+use std::slice;
+use std::ops::{
+    Add,
+    Sub,
+    Mul,
+    Div,
+    AddAssign,
+    SubAssign,
+    MulAssign,
+    DivAssign,
+    Neg,
+};
+
+use FastFloat;
+use Fast;
+
+/// A vector of `N` lanes of `Fast<F>`.
+#[repr(transparent)]
+#[derive(Debug, PartialEq)]
+pub struct FastSimd<F, const N: usize>(pub [Fast<F>; N]);
+
+impl<F, const N: usize> Copy for FastSimd<F, N>
+    where Fast<F>: Copy,
+{ }
+
+impl<F, const N: usize> Clone for FastSimd<F, N>
+    where Fast<F>: Copy,
+{
+    #[inline(always)]
+    fn clone(&self) -> Self {
+        *self
+    }
+}
+
+impl<F, const N: usize> FastSimd<F, N>
+    where Fast<F>: FastFloat,
+{
+    /// Return a vector with all lanes set to `x`.
+    #[inline(always)]
+    pub fn splat(x: Fast<F>) -> Self {
+        FastSimd([x; N])
+    }
+
+    /// Return a vector with the lanes of `xs`.
+    #[inline(always)]
+    pub fn from_array(xs: [Fast<F>; N]) -> Self {
+        FastSimd(xs)
+    }
+
+    /// Return the lanes as an array.
+    #[inline(always)]
+    pub fn to_array(self) -> [Fast<F>; N] {
+        self.0
+    }
+
+    /// Load the first `N` elements of `xs`, which need no particular
+    /// alignment.
+    ///
+    /// ***Panics*** if `xs` has fewer than `N` elements.
+    #[inline(always)]
+    pub fn from_slice_unaligned(xs: &[Fast<F>]) -> Self {
+        assert!(xs.len() >= N, "slice shorter than the lane count");
+        let mut v = [Fast::<F>::zero(); N];
+        v.copy_from_slice(&xs[..N]);
+        FastSimd(v)
+    }
+
+    /// Store the lanes into the first `N` elements of `xs`.
+    ///
+    /// ***Panics*** if `xs` has fewer than `N` elements.
+    #[inline(always)]
+    pub fn write_to_slice(self, xs: &mut [Fast<F>]) {
+        assert!(xs.len() >= N, "slice shorter than the lane count");
+        xs[..N].copy_from_slice(&self.0);
+    }
+
+    /// Load the first `N` elements of `xs`; lanes past the end of `xs` are
+    /// taken from `or`.
+    #[inline(always)]
+    pub fn load_or(xs: &[Fast<F>], or: Self) -> Self {
+        let mut v = or;
+        for (lane, &x) in v.0.iter_mut().zip(xs) {
+            *lane = x;
+        }
+        v
+    }
+
+    /// Load the lanes of the first `N` elements of `xs` where `mask` is
+    /// `true`; other lanes, and lanes past the end of `xs`, are taken from
+    /// `or`.
+    #[inline(always)]
+    pub fn load_select(xs: &[Fast<F>], mask: [bool; N], or: Self) -> Self {
+        let mut v = or;
+        for ((lane, &x), &m) in v.0.iter_mut().zip(xs).zip(&mask) {
+            if m {
+                *lane = x;
+            }
+        }
+        v
+    }
+
+    /// Load `xs[idxs[i]]` into lane `i`; lanes whose index is out of bounds
+    /// are taken from `or`.
+    #[inline(always)]
+    pub fn gather_or(xs: &[Fast<F>], idxs: [usize; N], or: Self) -> Self {
+        let mut v = or;
+        for (lane, &i) in v.0.iter_mut().zip(&idxs) {
+            if let Some(&x) = xs.get(i) {
+                *lane = x;
+            }
+        }
+        v
+    }
+
+    /// Store the lanes into the first `N` elements of `xs`, skipping lanes
+    /// past the end of `xs`.
+    #[inline(always)]
+    pub fn store_prefix(self, xs: &mut [Fast<F>]) {
+        for (x, &lane) in xs.iter_mut().zip(&self.0) {
+            *x = lane;
+        }
+    }
+
+    /// Store the lanes where `mask` is `true` into the first `N` elements of
+    /// `xs`, skipping lanes past the end of `xs`.
+    #[inline(always)]
+    pub fn store_select(self, xs: &mut [Fast<F>], mask: [bool; N]) {
+        for ((x, &lane), &m) in xs.iter_mut().zip(&self.0).zip(&mask) {
+            if m {
+                *x = lane;
+            }
+        }
+    }
+
+    /// Return a mask of the lanes that are in bounds for a slice of `len`
+    /// elements starting at lane zero.
+    #[inline(always)]
+    pub fn mask_prefix(len: usize) -> [bool; N] {
+        let mut mask = [false; N];
+        for (i, m) in mask.iter_mut().enumerate() {
+            *m = i < len;
+        }
+        mask
+    }
+
+    /// Return the sum of the lanes.
+    #[inline(always)]
+    pub fn reduce_sum(self) -> Fast<F> {
+        self.0.iter().fold(Fast::<F>::zero(), |acc, &x| acc + x)
+    }
+
+    /// Return `self * a + b`, lane by lane.
+    ///
+    /// These are fast operations, which the compiler fuses when the target
+    /// has FMA instructions.
+    #[inline(always)]
+    pub fn mul_add(self, a: Self, b: Self) -> Self {
+        self * a + b
+    }
+
+    /// Return `self * a + b`, lane by lane, with fused multiply-adds that
+    /// round only once.
+    ///
+    /// On targets without FMA instructions this is one call to the math
+    /// library per lane, so prefer [`mul_add`](#method.mul_add) unless the
+    /// single rounding is needed.
+    #[inline(always)]
+    pub fn mul_add_fused(self, a: Self, b: Self) -> Self {
+        let mut v = self;
+        for ((x, &a), &b) in v.0.iter_mut().zip(&a.0).zip(&b.0) {
+            *x = x.mul_add(a, b);
+        }
+        v
+    }
+}
+
+impl<F, const N: usize> From<[Fast<F>; N]> for FastSimd<F, N> {
+    #[inline(always)]
+    fn from(xs: [Fast<F>; N]) -> Self {
+        FastSimd(xs)
+    }
+}
+
+impl<F, const N: usize> From<FastSimd<F, N>> for [Fast<F>; N] {
+    #[inline(always)]
+    fn from(v: FastSimd<F, N>) -> Self {
+        v.0
+    }
+}
+
+macro_rules! impl_lane_fn {
+    ($($(#[$doc:meta])* fn $name:ident;)*) => {
+        impl<F, const N: usize> FastSimd<F, N>
+            where Fast<F>: FastFloat,
+        {
+            $(
+            $(#[$doc])*
+            #[inline(always)]
+            pub fn $name(mut self) -> Self {
+                for x in &mut self.0 {
+                    *x = x.$name();
+                }
+                self
+            }
+            )*
+
+            /// Return `self` raised to the power `n`, lane by lane.
+            #[inline(always)]
+            pub fn powf(mut self, n: Self) -> Self {
+                for (x, &n) in self.0.iter_mut().zip(&n.0) {
+                    *x = x.powf(n);
+                }
+                self
+            }
+        }
+    }
+}
+
+// With the `vecmath` feature these are inlined polynomials that vectorize,
+// otherwise one math library call per lane
+impl_lane_fn! {
+    /// Return `e^self`, lane by lane.
+    fn exp;
+    /// Return the natural logarithm, lane by lane.
+    fn ln;
+    /// Return the sine, lane by lane.
+    fn sin;
+    /// Return the cosine, lane by lane.
+    fn cos;
+    /// Return the square root, lane by lane.
+    fn sqrt;
+}
+
+macro_rules! impl_lane_op {
+    ($($name:ident, $method:ident, $assign:ident, $assign_method:ident;)*) => {
+        $(
+        impl<F, const N: usize> $name for FastSimd<F, N>
+            where Fast<F>: FastFloat,
+        {
+            type Output = Self;
+            #[inline(always)]
+            fn $method(mut self, rhs: Self) -> Self {
+                for (x, &y) in self.0.iter_mut().zip(&rhs.0) {
+                    *x = $name::$method(*x, y);
+                }
+                self
+            }
+        }
+
+        impl<F, const N: usize> $assign for FastSimd<F, N>
+            where Fast<F>: FastFloat,
+        {
+            #[inline(always)]
+            fn $assign_method(&mut self, rhs: Self) {
+                *self = $name::$method(*self, rhs);
+            }
+        }
+        )*
+    }
+}
+
+impl_lane_op! {
+    Add, add, AddAssign, add_assign;
+    Sub, sub, SubAssign, sub_assign;
+    Mul, mul, MulAssign, mul_assign;
+    Div, div, DivAssign, div_assign;
+}
+
+macro_rules! impl_scalar_op {
+    ($($name:ident, $method:ident;)*) => {
+        $(
+        impl<F, const N: usize> $name<Fast<F>> for FastSimd<F, N>
+            where Fast<F>: FastFloat,
+        {
+            type Output = Self;
+            #[inline(always)]
+            fn $method(mut self, rhs: Fast<F>) -> Self {
+                for x in &mut self.0 {
+                    *x = $name::$method(*x, rhs);
+                }
+                self
+            }
+        }
+        )*
+    }
+}
+
+// multiplication and division of every lane by a scalar
+impl_scalar_op! {
+    Mul, mul;
+    Div, div;
+}
+
+impl<F, const N: usize> Neg for FastSimd<F, N>
+    where Fast<F>: FastFloat,
+{
+    type Output = Self;
+    #[inline(always)]
+    fn neg(mut self) -> Self {
+        for x in &mut self.0 {
+            *x = -*x;
+        }
+        self
+    }
+}
+
+/// Fold `xs` in vectors of `N` lanes, starting from `init`.
+///
+/// Each full chunk of `N` elements is passed to `f` as a vector. The
+/// remaining tail of fewer than `N` elements, if any, is passed last, with
+/// the missing lanes set to `pad`, which should be the identity of the
+/// operation (for example zero for a sum).
+///
+/// ```
+/// use fast_floats::Fast;
+/// use fast_floats::simd::{FastSimd, fold_chunks_with_tail};
+///
+/// let xs = [1., 2., 3., 4., 5., 6., 7.].map(Fast);
+/// let acc = fold_chunks_with_tail(&xs, Fast(0.), FastSimd::<f64, 4>::splat(Fast(0.)),
+///                                 |acc, v| acc + v);
+/// assert_eq!(acc.reduce_sum(), Fast(28.));
+/// ```
+#[inline]
+pub fn fold_chunks_with_tail<F, const N: usize, B, G>(xs: &[Fast<F>], pad: Fast<F>, init: B,
+                                                      mut f: G) -> B
+    where Fast<F>: FastFloat,
+          G: FnMut(B, FastSimd<F, N>) -> B,
+{
+    let chunks = xs.chunks_exact(N);
+    let tail = chunks.remainder();
+    let mut acc = init;
+    for c in chunks {
+        acc = f(acc, FastSimd::load_or(c, FastSimd::splat(pad)));
+    }
+    if !tail.is_empty() {
+        acc = f(acc, FastSimd::load_or(tail, FastSimd::splat(pad)));
+    }
+    acc
+}
+
+/// Return the number of leading elements of `xs` to skip, so that the rest
+/// starts at a multiple of the vector width in bytes.
+#[inline]
+fn simd_offset<F, const N: usize>(xs: *const Fast<F>, len: usize) -> usize {
+    let width = N * mem::size_of::<Fast<F>>();
+    let offset = if width.is_power_of_two() {
+        xs.align_offset(width)
+    } else {
+        0
+    };
+    Ord::min(offset, len)
+}
+
+/// Split `xs` into a prefix, a middle of vectors and a suffix, like
+/// `slice::as_simd`.
+///
+/// The middle starts at an address aligned to the vector width in bytes
+/// (when that width is a power of two), and is as long as possible; the
+/// prefix and suffix each have fewer than `N` elements unless `xs` is too
+/// short to hold an aligned vector.
+///
+/// ```
+/// use fast_floats::Fast;
+/// use fast_floats::simd::{as_simd, FastSimd};
+///
+/// let xs = [1f32; 11].map(Fast);
+/// let (head, body, tail) = as_simd::<f32, 4>(&xs);
+/// let sum = body.iter().fold(FastSimd::splat(Fast(0.)), |acc, &v| acc + v).reduce_sum()
+///     + head.iter().chain(tail).fold(Fast(0.), |acc, &x| acc + x);
+/// assert_eq!(sum, Fast(11.));
+/// ```
+#[inline]
+#[allow(clippy::type_complexity)]
+pub fn as_simd<F, const N: usize>(xs: &[Fast<F>]) -> (&[Fast<F>], &[FastSimd<F, N>], &[Fast<F>]) {
+    assert!(N > 0, "lane count must be positive");
+    let (head, rest) = xs.split_at(simd_offset::<F, N>(xs.as_ptr(), xs.len()));
+    let (body, tail) = rest.split_at(rest.len() / N * N);
+    // FastSimd<F, N> is a transparent wrapper of [Fast<F>; N]
+    let body = unsafe {
+        slice::from_raw_parts(body.as_ptr() as *const FastSimd<F, N>, body.len() / N)
+    };
+    (head, body, tail)
+}
+
+/// Split `xs` into a prefix, a middle of vectors and a suffix, like
+/// `slice::as_simd_mut`.
+///
+/// See [`as_simd`](fn.as_simd.html).
+#[inline]
+#[allow(clippy::type_complexity)]
+pub fn as_simd_mut<F, const N: usize>(xs: &mut [Fast<F>])
+    -> (&mut [Fast<F>], &mut [FastSimd<F, N>], &mut [Fast<F>])
+{
+    assert!(N > 0, "lane count must be positive");
+    let offset = simd_offset::<F, N>(xs.as_ptr(), xs.len());
+    let (head, rest) = xs.split_at_mut(offset);
+    let body_len = rest.len() / N * N;
+    let (body, tail) = rest.split_at_mut(body_len);
+    // FastSimd<F, N> is a transparent wrapper of [Fast<F>; N]
+    let body = unsafe {
+        slice::from_raw_parts_mut(body.as_mut_ptr() as *mut FastSimd<F, N>, body_len / N)
+    };
+    (head, body, tail)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn masked_and_tail() {
+        let xs = [1., 2., 3.].map(Fast);
+        let zero = FastSimd::<f64, 4>::splat(Fast(0.));
+        assert_eq!(FastSimd::load_or(&xs, zero).0, [1., 2., 3., 0.].map(Fast));
+        let mask = FastSimd::<f64, 4>::mask_prefix(2);
+        assert_eq!(mask, [true, true, false, false]);
+        assert_eq!(FastSimd::load_select(&xs, mask, zero).0, [1., 2., 0., 0.].map(Fast));
+        let g = FastSimd::gather_or(&xs, [2, 0, 9, 1], zero);
+        assert_eq!(g.0, [3., 1., 0., 2.].map(Fast));
+
+        let mut out = [Fast(-1.); 3];
+        (g * g).store_prefix(&mut out);
+        assert_eq!(out, [9., 1., 0.].map(Fast));
+        (-g).store_select(&mut out, [false, true, true, true]);
+        assert_eq!(out, [9., -1., -0.].map(Fast));
+        assert_eq!(g.mul_add(g, g).reduce_sum(), Fast(12. + 2. + 6.));
+        assert_eq!(g.mul_add_fused(g, g), g.mul_add(g, g));
+
+        let ys = [1f32, 2., 3., 4., 5.].map(Fast);
+        let prod = fold_chunks_with_tail(&ys, Fast(1.), FastSimd::<f32, 2>::splat(Fast(1.)),
+                                         |acc, v| acc * v);
+        assert_eq!(prod.0[0] * prod.0[1], Fast(120.));
+    }
+
+    #[test]
+    fn array_and_slice() {
+        let xs = [1., 2., 3., 4., 5.].map(Fast);
+        let v = FastSimd::<f64, 4>::from_slice_unaligned(&xs[1..]);
+        assert_eq!(v.to_array(), [2., 3., 4., 5.].map(Fast));
+        assert_eq!(v, FastSimd::from_array([2., 3., 4., 5.].map(Fast)));
+
+        let mut out = [Fast(0.); 5];
+        (v + v).write_to_slice(&mut out[1..]);
+        assert_eq!(out, [0., 4., 6., 8., 10.].map(Fast));
+        let arr: [Fast<f64>; 4] = v.into();
+        assert_eq!(arr[0], Fast(2.));
+
+        let w = FastSimd::<f32, 4>::from_array([0., 1., 2., 3.].map(Fast));
+        let e = w.exp().ln().powf(FastSimd::splat(Fast(2.)));
+        for (x, y) in e.to_array().iter().zip(&[0f32, 1., 4., 9.]) {
+            assert!((x.get() - y).abs() < 1e-5);
+        }
+        assert!((w.sin() * w.sin() + w.cos() * w.cos()).to_array().iter()
+                .all(|x| (x.get() - 1.).abs() < 1e-6));
+    }
+
+    #[test]
+    fn split_aligned() {
+        let mut xs = [0f64; 19].map(Fast);
+        for (i, x) in xs.iter_mut().enumerate() {
+            *x = Fast(i as f64);
+        }
+        let (head, body, tail) = as_simd::<f64, 4>(&xs);
+        assert_eq!(head.len() + 4 * body.len() + tail.len(), 19);
+        assert!(head.len() < 4 && tail.len() < 4);
+        assert_eq!(body.as_ptr() as usize % 32, 0);
+        assert_eq!(body[0].0[0], Fast(head.len() as f64));
+
+        let (head, body, _) = as_simd_mut::<f64, 4>(&mut xs);
+        let (start, end) = (head.len(), head.len() + 4 * body.len());
+        for v in body {
+            *v = -*v;
+        }
+        for (i, x) in xs.iter().enumerate() {
+            let sign = if start <= i && i < end { -1. } else { 1. };
+            assert_eq!(*x, Fast(sign * i as f64));
+        }
+    }
+}
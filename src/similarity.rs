@@ -0,0 +1,169 @@
+//! Similarity and distance kernels for pairs of vectors.
+//!
+//! If the two vectors have different lengths, the extra elements of the
+//! longer one are ignored.
+
+use std::cmp::min;
+
+use FastFloat;
+
+use reduce::{merge, ACCS};
+
+pub use reduce::dot;
+
+/// Return the cosine similarity of `xs` and `ys`: their dot product divided
+/// by the product of their norms.
+///
+/// The dot product and both norms are computed in one pass. Return zero if
+/// either vector is zero.
+///
+/// ```
+/// use fast_floats::Fast;
+/// use fast_floats::similarity::cosine;
+///
+/// let a = [1., 0.].map(Fast);
+/// let b = [1., 1.].map(Fast);
+/// assert!((cosine(&a, &b).get() - 0.5f64.sqrt()).abs() < 1e-12);
+/// ```
+pub fn cosine<F: FastFloat>(xs: &[F], ys: &[F]) -> F {
+    let n = min(xs.len(), ys.len());
+    let (xs, ys) = (&xs[..n], &ys[..n]);
+    let mut xy = [F::zero(); ACCS];
+    let mut xx = [F::zero(); ACCS];
+    let mut yy = [F::zero(); ACCS];
+    let xc = xs.chunks_exact(ACCS);
+    let yc = ys.chunks_exact(ACCS);
+    let (xt, yt) = (xc.remainder(), yc.remainder());
+    for (x, y) in xc.zip(yc) {
+        for j in 0..ACCS {
+            xy[j] += x[j] * y[j];
+            xx[j] += x[j] * x[j];
+            yy[j] += y[j] * y[j];
+        }
+    }
+    for (j, (&x, &y)) in xt.iter().zip(yt).enumerate() {
+        xy[j] += x * y;
+        xx[j] += x * x;
+        yy[j] += y * y;
+    }
+    // the product of the squared norms would underflow or overflow sooner
+    let norms = merge(xx).sqrt() * merge(yy).sqrt();
+    if norms == F::zero() {
+        F::zero()
+    } else {
+        merge(xy) / norms
+    }
+}
+
+/// Return the squared Euclidean distance between `xs` and `ys`.
+pub fn euclidean_squared<F: FastFloat>(xs: &[F], ys: &[F]) -> F {
+    let n = min(xs.len(), ys.len());
+    let (xs, ys) = (&xs[..n], &ys[..n]);
+    let mut acc = [F::zero(); ACCS];
+    let xc = xs.chunks_exact(ACCS);
+    let yc = ys.chunks_exact(ACCS);
+    let (xt, yt) = (xc.remainder(), yc.remainder());
+    for (x, y) in xc.zip(yc) {
+        for j in 0..ACCS {
+            let d = x[j] - y[j];
+            acc[j] += d * d;
+        }
+    }
+    for ((a, &x), &y) in acc.iter_mut().zip(xt).zip(yt) {
+        let d = x - y;
+        *a += d * d;
+    }
+    merge(acc)
+}
+
+/// Return the Euclidean distance between `xs` and `ys`.
+#[inline]
+pub fn euclidean<F: FastFloat>(xs: &[F], ys: &[F]) -> F {
+    euclidean_squared(xs, ys).sqrt()
+}
+
+/// Compute the Euclidean distance from every row of `a` to every row of
+/// `b`, writing the `m × n` distance matrix in row-major order to `out`.
+///
+/// `a` and `b` are row-major matrices of `m` and `n` rows with `dim` columns
+/// each. Rows of `b` are processed in blocks, so that each row of `a` is
+/// reused across several accumulators.
+///
+/// ***Panics*** if `dim` is zero, if the lengths of `a` or `b` are not
+/// multiples of `dim`, or if `out.len()` is not `m * n`.
+///
+/// ```
+/// use fast_floats::Fast;
+/// use fast_floats::similarity::pairwise_distances;
+///
+/// let a = [0., 0., 1., 1.].map(Fast);
+/// let b = [3., 4., 0., 0., 1., 1.].map(Fast);
+/// let mut out = [Fast(0.); 6];
+/// pairwise_distances(&a, &b, 2, &mut out);
+/// assert_eq!(out[..3], [5., 0., 2f64.sqrt()].map(Fast));
+/// ```
+pub fn pairwise_distances<F: FastFloat>(a: &[F], b: &[F], dim: usize, out: &mut [F]) {
+    assert!(dim != 0, "pairwise_distances: zero dim");
+    assert!(a.len().is_multiple_of(dim) && b.len().is_multiple_of(dim),
+            "pairwise_distances: length is not a multiple of dim");
+    let n = b.len() / dim;
+    assert_eq!(out.len(), a.len() / dim * n,
+               "pairwise_distances: wrong output length");
+    if n == 0 {
+        return;
+    }
+    for (x, row) in a.chunks_exact(dim).zip(out.chunks_exact_mut(n)) {
+        let blocks = b.chunks_exact(ACCS * dim);
+        let tail = blocks.remainder();
+        let mut j = 0;
+        for ys in blocks {
+            let mut acc = [F::zero(); ACCS];
+            for k in 0..dim {
+                for r in 0..ACCS {
+                    let d = x[k] - ys[r * dim + k];
+                    acc[r] += d * d;
+                }
+            }
+            for r in 0..ACCS {
+                row[j + r] = acc[r].sqrt();
+            }
+            j += ACCS;
+        }
+        for y in tail.chunks_exact(dim) {
+            row[j] = euclidean(x, y);
+            j += 1;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use Fast;
+
+    #[test]
+    fn cosine_euclidean() {
+        let a = [1f64, 2., 3., 4., 5.].map(Fast);
+        let b = [2., 4., 6., 8., 10.].map(Fast);
+        assert!((cosine(&a, &b).get() - 1.).abs() < 1e-12);
+        assert_eq!(cosine(&a, &[Fast(0.); 5]), Fast(0.));
+        let (ta, tb) = (a.map(|x| x * Fast(1e-80)), b.map(|x| x * Fast(1e-80)));
+        assert!((cosine(&ta, &tb).get() - 1.).abs() < 1e-12);
+        assert_eq!(euclidean_squared(&a, &b), Fast(55.));
+        assert_eq!(euclidean(&[Fast(0f32), Fast(0.)], &[Fast(3.), Fast(4.)]), Fast(5.));
+        assert_eq!(dot(&a, &b), Fast(110.));
+    }
+
+    #[test]
+    fn pairwise() {
+        let a = [1f32, 2., 3., 4., 5., 6.].map(Fast);
+        let b = [0f32, 0., 1., 2., 3., 4., 5., 6., 7., 8., 9., 10.].map(Fast);
+        let mut out = [Fast(0.); 18];
+        pairwise_distances(&a, &b, 2, &mut out);
+        for (i, x) in a.chunks(2).enumerate() {
+            for (j, y) in b.chunks(2).enumerate() {
+                assert_eq!(out[i * 6 + j], euclidean(x, y));
+            }
+        }
+    }
+}
@@ -0,0 +1,181 @@
+//! Evenly spaced sequences: linear, logarithmic and geometric.
+
+use FastFloat;
+
+/// An iterator of `n` evenly spaced floats.
+///
+/// Created with [`linspace`](fn.linspace.html).
+#[derive(Clone, Debug)]
+pub struct Linspace<F> {
+    start: F,
+    step: F,
+    index: usize,
+    len: usize,
+}
+
+impl<F: FastFloat> Iterator for Linspace<F> {
+    type Item = F;
+
+    #[inline]
+    fn next(&mut self) -> Option<F> {
+        if self.index >= self.len {
+            None
+        } else {
+            let i = self.index;
+            self.index += 1;
+            Some(self.start + self.step * F::from_usize(i))
+        }
+    }
+
+    #[inline]
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let n = self.len - self.index;
+        (n, Some(n))
+    }
+}
+
+impl<F: FastFloat> DoubleEndedIterator for Linspace<F> {
+    #[inline]
+    fn next_back(&mut self) -> Option<F> {
+        if self.index >= self.len {
+            None
+        } else {
+            self.len -= 1;
+            Some(self.start + self.step * F::from_usize(self.len))
+        }
+    }
+}
+
+impl<F: FastFloat> ExactSizeIterator for Linspace<F> {}
+
+/// Return an iterator of `n` evenly spaced floats from `a` to `b`, inclusive.
+///
+/// ```
+/// use fast_floats::Fast;
+/// use fast_floats::space::linspace;
+///
+/// let xs: Vec<_> = linspace(Fast(0.), Fast(1.), 5).map(Fast::get).collect();
+/// assert_eq!(xs, [0., 0.25, 0.5, 0.75, 1.]);
+/// ```
+#[inline]
+pub fn linspace<F: FastFloat>(a: F, b: F, n: usize) -> Linspace<F> {
+    let step = if n > 1 {
+        (b - a) / F::from_usize(n - 1)
+    } else {
+        F::zero()
+    };
+    Linspace {
+        start: a,
+        step,
+        index: 0,
+        len: n,
+    }
+}
+
+/// An iterator of `n` logarithmically spaced floats.
+///
+/// Created with [`logspace`](fn.logspace.html) or
+/// [`geomspace`](fn.geomspace.html).
+#[derive(Clone, Debug)]
+pub struct Logspace<F> {
+    sign: F,
+    log2_base: F,
+    exponents: Linspace<F>,
+}
+
+impl<F: FastFloat> Iterator for Logspace<F> {
+    type Item = F;
+
+    #[inline]
+    fn next(&mut self) -> Option<F> {
+        let (sign, log2_base) = (self.sign, self.log2_base);
+        self.exponents.next().map(|x| sign * (x * log2_base).exp2())
+    }
+
+    #[inline]
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.exponents.size_hint()
+    }
+}
+
+impl<F: FastFloat> DoubleEndedIterator for Logspace<F> {
+    #[inline]
+    fn next_back(&mut self) -> Option<F> {
+        let (sign, log2_base) = (self.sign, self.log2_base);
+        self.exponents.next_back().map(|x| sign * (x * log2_base).exp2())
+    }
+}
+
+impl<F: FastFloat> ExactSizeIterator for Logspace<F> {}
+
+/// Return an iterator of `n` floats from `base^a` to `base^b`, inclusive,
+/// evenly spaced on a log scale.
+///
+/// `base` must be positive.
+///
+/// ```
+/// use fast_floats::Fast;
+/// use fast_floats::space::logspace;
+///
+/// let xs: Vec<_> = logspace(Fast(2.), Fast(0.), Fast(3.), 4).map(Fast::get).collect();
+/// assert_eq!(xs, [1., 2., 4., 8.]);
+/// ```
+#[inline]
+pub fn logspace<F: FastFloat>(base: F, a: F, b: F, n: usize) -> Logspace<F> {
+    Logspace {
+        sign: F::one(),
+        log2_base: base.log2(),
+        exponents: linspace(a, b, n),
+    }
+}
+
+/// Return an iterator of `n` floats from `a` to `b`, inclusive, where each
+/// element is a constant multiple of the previous one.
+///
+/// Return `None` if `a` and `b` are zero or have different signs.
+///
+/// ```
+/// use fast_floats::Fast;
+/// use fast_floats::space::geomspace;
+///
+/// let xs: Vec<_> = geomspace(Fast(-1f64), Fast(-1000.), 4).unwrap().collect();
+/// assert_eq!(xs.len(), 4);
+/// assert!((xs[1].get() + 10.).abs() < 1e-9);
+/// ```
+#[inline]
+pub fn geomspace<F: FastFloat>(a: F, b: F, n: usize) -> Option<Logspace<F>> {
+    let zero = F::zero();
+    if a == zero || b == zero || (a < zero) != (b < zero) {
+        return None;
+    }
+    Some(Logspace {
+        sign: if a < zero { -F::one() } else { F::one() },
+        log2_base: F::one(),
+        exponents: linspace(a.abs().log2(), b.abs().log2(), n),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use Fast;
+
+    #[test]
+    fn lin_log_geom() {
+        let mut xs = linspace(Fast(1.), Fast(3.), 3);
+        assert_eq!(xs.len(), 3);
+        assert_eq!(xs.next_back(), Some(Fast(3.)));
+        assert_eq!(xs.next(), Some(Fast(1.)));
+        assert_eq!(xs.next(), Some(Fast(2.)));
+        assert_eq!(xs.next(), None);
+
+        for (x, y) in logspace(Fast(10.), Fast(0.), Fast(3.), 4).zip(&[1f64, 10., 100., 1000.]) {
+            assert!((x.get() - y).abs() < 1e-9 * y);
+        }
+        for (x, y) in geomspace(Fast(1e-3f32), Fast(1.), 4).unwrap().zip(&[1e-3f32, 1e-2, 1e-1, 1.]) {
+            assert!((x.get() - y).abs() < 1e-5 * y);
+        }
+        assert!(geomspace(Fast(-1.), Fast(1.), 4).is_none());
+        assert!(geomspace(Fast(0.), Fast(1.), 4).is_none());
+    }
+}
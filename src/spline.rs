@@ -0,0 +1,109 @@
+//! Cubic spline evaluation.
+//!
+//! The points can be scalars, or anything with the vector space operations
+//! over a scalar: `FastSimd` lanes of the same float type, or point structs
+//! with `#[derive(FastOps)]`, which evaluate all components at once. The
+//! parameter `t` is a scalar. Each curve is expanded to its power basis
+//! coefficients and evaluated in Horner form.
+
+use std::ops::{Add, Sub, Mul};
+
+use FastFloat;
+
+#[inline(always)]
+fn cubic<P, F>(c0: P, c1: P, c2: P, c3: P, t: F) -> P
+    where P: Copy + Add<Output=P> + Mul<F, Output=P>,
+          F: FastFloat,
+{
+    ((c3 * t + c2) * t + c1) * t + c0
+}
+
+/// Evaluate the cubic Bézier curve with control points `p0, p1, p2, p3` at
+/// `t` in `0..=1`.
+///
+/// ```
+/// use fast_floats::Fast;
+/// use fast_floats::spline::cubic_bezier;
+///
+/// let [p0, p1, p2, p3] = [0., 1., 3., 4.].map(Fast);
+/// assert_eq!(cubic_bezier(p0, p1, p2, p3, Fast(0.)), p0);
+/// assert_eq!(cubic_bezier(p0, p1, p2, p3, Fast(0.5)), Fast(2.));
+/// assert_eq!(cubic_bezier(p0, p1, p2, p3, Fast(1.)), p3);
+/// ```
+#[inline]
+pub fn cubic_bezier<P, F>(p0: P, p1: P, p2: P, p3: P, t: F) -> P
+    where P: Copy + Add<Output=P> + Sub<Output=P> + Mul<F, Output=P>,
+          F: FastFloat,
+{
+    let three = F::from_f64(3.);
+    let c1 = (p1 - p0) * three;
+    let c2 = (p2 - p1 - p1 + p0) * three;
+    let c3 = p3 - p0 + (p1 - p2) * three;
+    cubic(p0, c1, c2, c3, t)
+}
+
+/// Evaluate the uniform Catmull–Rom spline segment between `p1` and `p2` at
+/// `t` in `0..=1`, where `p0` and `p3` are the neighbouring points.
+///
+/// The curve passes through `p1` at `t = 0` and `p2` at `t = 1`, so
+/// consecutive segments of a sequence of points join smoothly.
+///
+/// ```
+/// use fast_floats::Fast;
+/// use fast_floats::spline::catmull_rom;
+///
+/// let [p0, p1, p2, p3] = [0., 1., 2., 3.].map(Fast);
+/// assert_eq!(catmull_rom(p0, p1, p2, p3, Fast(0.)), p1);
+/// assert_eq!(catmull_rom(p0, p1, p2, p3, Fast(0.25)), Fast(1.25));
+/// ```
+#[inline]
+pub fn catmull_rom<P, F>(p0: P, p1: P, p2: P, p3: P, t: F) -> P
+    where P: Copy + Add<Output=P> + Sub<Output=P> + Mul<F, Output=P>,
+          F: FastFloat,
+{
+    let half = F::from_f64(0.5);
+    let (two, three) = (F::from_f64(2.), F::from_f64(3.));
+    let c1 = (p2 - p0) * half;
+    let c2 = (p0 * two - p1 * F::from_f64(5.) + p2 * F::from_f64(4.) - p3) * half;
+    let c3 = (p3 - p0 + (p1 - p2) * three) * half;
+    cubic(p1, c1, c2, c3, t)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use Fast;
+    use simd::FastSimd;
+
+    #[test]
+    fn curves() {
+        let p = [0.5f64, -1., 2., 3.].map(Fast);
+        for i in 0..=8 {
+            let t = Fast(i as f64 / 8.);
+            let s = Fast(1.) - t;
+            let bernstein = s * s * s * p[0] + Fast(3.) * s * s * t * p[1]
+                + Fast(3.) * s * t * t * p[2] + t * t * t * p[3];
+            assert!((cubic_bezier(p[0], p[1], p[2], p[3], t) - bernstein).abs() < Fast(1e-12));
+        }
+        assert_eq!(catmull_rom(p[0], p[1], p[2], p[3], Fast(1.)), p[2]);
+        // tangent at p1 is (p2 - p0) / 2
+        let h = Fast(1e-6);
+        let slope = (catmull_rom(p[0], p[1], p[2], p[3], h) - p[1]) / h;
+        assert!((slope - Fast(0.75)).abs() < Fast(1e-5));
+    }
+
+    #[test]
+    fn simd_points() {
+        // the lanes are the coordinates of points in the plane
+        let p = [[0.5f64, 1.], [-1., 2.], [2., 0.], [3., 4.]].map(|c| FastSimd(c.map(Fast)));
+        for i in 0..=8 {
+            let t = Fast(i as f64 / 8.);
+            let b = cubic_bezier(p[0], p[1], p[2], p[3], t);
+            let c = catmull_rom(p[0], p[1], p[2], p[3], t);
+            for j in 0..2 {
+                assert_eq!(b.0[j], cubic_bezier(p[0].0[j], p[1].0[j], p[2].0[j], p[3].0[j], t));
+                assert_eq!(c.0[j], catmull_rom(p[0].0[j], p[1].0[j], p[2].0[j], p[3].0[j], t));
+            }
+        }
+    }
+}
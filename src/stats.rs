@@ -0,0 +1,427 @@
+//! Statistics kernels over slices of fast floats.
+
+use std::cmp::min;
+
+use FastFloat;
+use reduce;
+
+pub mod normal;
+
+/// Count `values` into equal width bins spanning `range`, inclusive of
+/// both ends.
+///
+/// The number of bins is `counts.len()`, and counts are added to the
+/// existing contents of `counts`, so that a large data set can be binned in
+/// several calls. Values outside of `range` (and NaN) are not counted.
+///
+/// ***Panics*** if `range.0 >= range.1`.
+///
+/// ```
+/// use fast_floats::Fast;
+/// use fast_floats::stats::histogram;
+///
+/// let xs = [0., 0.5, 1.5, 2., 3.9, 4., 5.].iter().map(|&x| Fast(x)).collect::<Vec<_>>();
+/// let mut counts = [0; 4];
+/// histogram(&xs, (Fast(0.), Fast(4.)), &mut counts);
+/// assert_eq!(counts, [2, 1, 1, 2]);
+/// ```
+pub fn histogram<F: FastFloat>(values: &[F], range: (F, F), counts: &mut [usize]) {
+    let (lo, hi) = range;
+    assert!(lo < hi, "histogram: empty range");
+    let bins = counts.len();
+    if bins == 0 {
+        return;
+    }
+    let scale = F::from_usize(bins) / (hi - lo);
+    for &x in values {
+        if x >= lo && x <= hi {
+            // x == hi lands in the last bin
+            let i = ((x - lo) * scale).to_usize();
+            counts[if i < bins { i } else { bins - 1 }] += 1;
+        }
+    }
+}
+
+/// Return the median of `xs`, or `None` if it is empty.
+///
+/// `xs` is used as scratch space and is reordered. Elements are ordered by
+/// `total_cmp`, so that NaNs are sorted to the ends instead of poisoning the
+/// selection. For an even number of elements, the mean of the two middle
+/// elements is returned.
+pub fn median<F: FastFloat>(xs: &mut [F]) -> Option<F> {
+    let n = xs.len();
+    if n == 0 {
+        return None;
+    }
+    let (left, &mut mid, _) = xs.select_nth_unstable_by(n / 2, F::total_cmp);
+    if n % 2 == 1 {
+        Some(mid)
+    } else {
+        let below = max_total(left);
+        Some((below + mid) / F::from_f64(2.))
+    }
+}
+
+/// Return the `p`th percentile of `xs`, or `None` if it is empty.
+///
+/// `p` is in the range `0` to `100`, and values between ranks are linearly
+/// interpolated. `xs` is used as scratch space and is reordered, as in
+/// [`median`](fn.median.html).
+///
+/// ***Panics*** if `p` is not in `0..=100`.
+///
+/// ```
+/// use fast_floats::Fast;
+/// use fast_floats::stats::percentile;
+///
+/// let mut xs = [4., 1., 3., 2., 5.].map(Fast);
+/// assert_eq!(percentile(&mut xs, Fast(25.)), Some(Fast(2.)));
+/// assert_eq!(percentile(&mut xs, Fast(90.)), Some(Fast(4.6)));
+/// ```
+pub fn percentile<F: FastFloat>(xs: &mut [F], p: F) -> Option<F> {
+    assert!(p >= F::zero() && p <= F::from_f64(100.),
+            "percentile: p must be in 0..=100");
+    let n = xs.len();
+    if n == 0 {
+        return None;
+    }
+    let rank = p / F::from_f64(100.) * F::from_usize(n - 1);
+    let k = rank.to_usize();
+    let frac = rank - F::from_usize(k);
+    let (_, &mut lower, right) = xs.select_nth_unstable_by(k, F::total_cmp);
+    if right.is_empty() {
+        Some(lower)
+    } else {
+        let upper = min_total(right);
+        Some(lower + (upper - lower) * frac)
+    }
+}
+
+/// Select the `k` largest of `scores`, where `k` is `out.len()`, and write
+/// them with their indices to `out` in descending order.
+///
+/// Return the filled part of `out`, which is shorter than `k` if `scores`
+/// has fewer than `k` elements. The selection uses a bounded min-heap, so
+/// that the whole input is not sorted, and elements are ordered by
+/// `total_cmp`.
+///
+/// ```
+/// use fast_floats::Fast;
+/// use fast_floats::stats::topk;
+///
+/// let scores = [0.1, 0.7, 0.3, 0.9, 0.5].map(Fast);
+/// let mut best = [(0, Fast(0.)); 2];
+/// assert_eq!(topk(&scores, &mut best), [(3, Fast(0.9)), (1, Fast(0.7))]);
+/// ```
+pub fn topk<'a, F: FastFloat>(scores: &[F], out: &'a mut [(usize, F)]) -> &'a mut [(usize, F)] {
+    let k = min(out.len(), scores.len());
+    let heap = &mut out[..k];
+    if k == 0 {
+        return heap;
+    }
+    for (i, slot) in heap.iter_mut().enumerate() {
+        *slot = (i, scores[i]);
+    }
+    for i in (0..k / 2).rev() {
+        sift_down(heap, i);
+    }
+    for (i, &x) in scores.iter().enumerate().skip(k) {
+        if x.total_cmp(&heap[0].1).is_gt() {
+            heap[0] = (i, x);
+            sift_down(heap, 0);
+        }
+    }
+    heap.sort_unstable_by(|a, b| b.1.total_cmp(&a.1).then(a.0.cmp(&b.0)));
+    heap
+}
+
+/// Restore the min-heap property below `i`
+fn sift_down<F: FastFloat>(heap: &mut [(usize, F)], mut i: usize) {
+    loop {
+        let left = 2 * i + 1;
+        if left >= heap.len() {
+            break;
+        }
+        let right = left + 1;
+        let child = if right < heap.len() && heap[right].1.total_cmp(&heap[left].1).is_lt() {
+            right
+        } else {
+            left
+        };
+        if heap[child].1.total_cmp(&heap[i].1).is_lt() {
+            heap.swap(i, child);
+            i = child;
+        } else {
+            break;
+        }
+    }
+}
+
+/// Return the arithmetic mean of `xs`, or `None` if it is empty.
+pub fn mean<F: FastFloat>(xs: &[F]) -> Option<F> {
+    if xs.is_empty() {
+        None
+    } else {
+        Some(reduce::sum(xs) / F::from_usize(xs.len()))
+    }
+}
+
+/// Return the population variance of `xs`, or `None` if it is empty.
+pub fn variance<F: FastFloat>(xs: &[F]) -> Option<F> {
+    let m = mean(xs)?;
+    Some(variance_about(xs, m))
+}
+
+/// Return the mean of the squared deviations of `xs` from `m`, which must
+/// not be empty.
+fn variance_about<F: FastFloat>(xs: &[F], m: F) -> F {
+    let mut acc = [F::zero(); 4];
+    let chunks = xs.chunks_exact(4);
+    let tail = chunks.remainder();
+    for c in chunks {
+        for j in 0..4 {
+            let d = c[j] - m;
+            acc[j] += d * d;
+        }
+    }
+    for (a, &x) in acc.iter_mut().zip(tail) {
+        let d = x - m;
+        *a += d * d;
+    }
+    let ss = (acc[0] + acc[1]) + (acc[2] + acc[3]);
+    ss / F::from_usize(xs.len())
+}
+
+/// Aggregates of a slice, computed by [`summary`](fn.summary.html).
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct Summary<F> {
+    /// Sum of the elements
+    pub sum: F,
+    /// Sum of the squares of the elements
+    pub sum_sq: F,
+    /// Smallest element
+    pub min: F,
+    /// Largest element
+    pub max: F,
+    /// Number of elements
+    pub count: usize,
+}
+
+impl<F: FastFloat> Summary<F> {
+    /// Return the arithmetic mean.
+    #[inline]
+    pub fn mean(&self) -> F {
+        self.sum / F::from_usize(self.count)
+    }
+
+    /// Return the population variance, `sum_sq / count - mean²`.
+    ///
+    /// This loses precision to cancellation when the mean is large compared
+    /// to the spread; [`variance`](fn.variance.html) is more accurate.
+    #[inline]
+    pub fn variance(&self) -> F {
+        let m = self.mean();
+        (self.sum_sq / F::from_usize(self.count) - m * m).max(F::zero())
+    }
+}
+
+/// Return the sum, sum of squares, minimum, maximum and count of `xs` in a
+/// single pass, or `None` if it is empty.
+///
+/// ```
+/// use fast_floats::Fast;
+/// use fast_floats::stats::summary;
+///
+/// let s = summary(&[3., 1., 4., 1., 5.].map(Fast)).unwrap();
+/// assert_eq!((s.sum, s.sum_sq, s.min, s.max, s.count),
+///            (Fast(14.), Fast(52.), Fast(1.), Fast(5.), 5));
+/// ```
+pub fn summary<F: FastFloat>(xs: &[F]) -> Option<Summary<F>> {
+    let &first = xs.first()?;
+    let mut sum = [F::zero(); 4];
+    let mut sum_sq = [F::zero(); 4];
+    let mut lo = [first; 4];
+    let mut hi = [first; 4];
+    let chunks = xs.chunks_exact(4);
+    let tail = chunks.remainder();
+    for c in chunks {
+        for j in 0..4 {
+            sum[j] += c[j];
+            sum_sq[j] += c[j] * c[j];
+            lo[j] = lo[j].min(c[j]);
+            hi[j] = hi[j].max(c[j]);
+        }
+    }
+    for (j, &x) in tail.iter().enumerate() {
+        sum[j] += x;
+        sum_sq[j] += x * x;
+        lo[j] = lo[j].min(x);
+        hi[j] = hi[j].max(x);
+    }
+    Some(Summary {
+        sum: (sum[0] + sum[1]) + (sum[2] + sum[3]),
+        sum_sq: (sum_sq[0] + sum_sq[1]) + (sum_sq[2] + sum_sq[3]),
+        min: lo[0].min(lo[1]).min(lo[2].min(lo[3])),
+        max: hi[0].max(hi[1]).max(hi[2].max(hi[3])),
+        count: xs.len(),
+    })
+}
+
+/// Standardize `xs` in place to zero mean and unit variance, by subtracting
+/// the mean and dividing by the (population) standard deviation.
+///
+/// Return the original `(mean, stddev)`, or `None` if `xs` is empty. If the
+/// standard deviation is at most `epsilon · |mean|`, which is within
+/// rounding error of zero, as for a constant slice, the slice is only
+/// centered and not scaled.
+///
+/// ```
+/// use fast_floats::Fast;
+/// use fast_floats::stats::standardize_in_place;
+///
+/// let mut xs = [1f64, 3., 5., 7.].map(Fast);
+/// let (mean, _) = standardize_in_place(&mut xs).unwrap();
+/// assert_eq!(mean, Fast(4.));
+/// assert!((xs[0].get() + 1.3416).abs() < 1e-4);
+/// ```
+pub fn standardize_in_place<F: FastFloat>(xs: &mut [F]) -> Option<(F, F)> {
+    let m = mean(xs)?;
+    let sd = variance_about(xs, m).sqrt();
+    let scale = if sd <= F::epsilon() * m.abs() { F::one() } else { F::one() / sd };
+    for x in xs.iter_mut() {
+        *x = (*x - m) * scale;
+    }
+    Some((m, sd))
+}
+
+/// Rescale `xs` in place to the range `0` to `1`, mapping its minimum to
+/// `0` and its maximum to `1`.
+///
+/// Return the original `(min, max)`, or `None` if `xs` is empty. If all
+/// elements are equal, they are all set to `0`.
+///
+/// ```
+/// use fast_floats::Fast;
+/// use fast_floats::stats::normalize_minmax_in_place;
+///
+/// let mut xs = [2., 4., 3., 6.].map(Fast);
+/// assert_eq!(normalize_minmax_in_place(&mut xs), Some((Fast(2.), Fast(6.))));
+/// assert_eq!(xs, [0., 0.5, 0.25, 1.].map(Fast));
+/// ```
+pub fn normalize_minmax_in_place<F: FastFloat>(xs: &mut [F]) -> Option<(F, F)> {
+    let (&first, rest) = xs.split_first()?;
+    let (mut lo, mut hi) = (first, first);
+    for &x in rest {
+        lo = lo.min(x);
+        hi = hi.max(x);
+    }
+    if lo == hi {
+        for x in xs.iter_mut() {
+            *x = F::zero();
+        }
+    } else {
+        let scale = F::one() / (hi - lo);
+        for x in xs.iter_mut() {
+            *x = (*x - lo) * scale;
+        }
+    }
+    Some((lo, hi))
+}
+
+fn min_total<F: FastFloat>(xs: &[F]) -> F {
+    let mut m = xs[0];
+    for x in &xs[1..] {
+        if x.total_cmp(&m).is_lt() {
+            m = *x;
+        }
+    }
+    m
+}
+
+fn max_total<F: FastFloat>(xs: &[F]) -> F {
+    let mut m = xs[0];
+    for x in &xs[1..] {
+        if x.total_cmp(&m).is_gt() {
+            m = *x;
+        }
+    }
+    m
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use Fast;
+
+    #[test]
+    fn histogram_f32() {
+        let xs = [-1., 0., 0.1, 0.25, 0.3, 0.99, 1., 1.5, f32::NAN].map(Fast);
+        let mut counts = [0; 4];
+        histogram(&xs, (Fast(0.), Fast(1.)), &mut counts);
+        histogram(&xs[..2], (Fast(0.), Fast(1.)), &mut counts);
+        assert_eq!(counts, [3, 2, 0, 2]);
+    }
+
+    #[test]
+    fn median_percentile() {
+        let mut xs = [5., 3., 1., 4., 2., 6.].map(Fast);
+        assert_eq!(median(&mut xs), Some(Fast(3.5)));
+        assert_eq!(median(&mut xs[..5]), Some(Fast(3.)));
+        assert_eq!(median::<Fast<f64>>(&mut []), None);
+        assert_eq!(percentile(&mut xs, Fast(0.)), Some(Fast(1.)));
+        assert_eq!(percentile(&mut xs, Fast(100.)), Some(Fast(6.)));
+        assert_eq!(percentile(&mut xs, Fast(50.)), Some(Fast(3.5)));
+    }
+
+    #[test]
+    fn topk_select() {
+        let scores = [3., 9., 1., 7., 9., 2., 8.].map(Fast);
+        let mut out = [(0, Fast(0.)); 3];
+        assert_eq!(topk(&scores, &mut out), [(1, Fast(9.)), (4, Fast(9.)), (6, Fast(8.))]);
+        let mut out = [(0, Fast(0.)); 10];
+        assert_eq!(topk(&scores[..2], &mut out), [(1, Fast(9.)), (0, Fast(3.))]);
+        assert_eq!(topk(&scores, &mut []), []);
+    }
+
+    #[test]
+    fn normalize_minmax() {
+        let mut xs = [-1f32, 1., 0.].map(Fast);
+        assert_eq!(normalize_minmax_in_place(&mut xs), Some((Fast(-1.), Fast(1.))));
+        assert_eq!(xs, [0., 1., 0.5].map(Fast));
+        let mut xs = [7f32; 3].map(Fast);
+        normalize_minmax_in_place(&mut xs);
+        assert_eq!(xs, [Fast(0.); 3]);
+        assert_eq!(normalize_minmax_in_place::<Fast<f32>>(&mut []), None);
+    }
+
+    #[test]
+    fn standardize() {
+        let mut xs = [2., 4., 4., 4., 5., 5., 7., 9.].map(Fast);
+        assert_eq!(mean(&xs), Some(Fast(5.)));
+        assert_eq!(variance(&xs), Some(Fast(4.)));
+        assert_eq!(standardize_in_place(&mut xs), Some((Fast(5.), Fast(2.))));
+        assert_eq!(xs[0], Fast(-1.5));
+        assert_eq!(mean(&xs), Some(Fast(0.)));
+        let mut xs = [3.; 5].map(Fast);
+        assert_eq!(standardize_in_place(&mut xs), Some((Fast(3.), Fast(0.))));
+        assert_eq!(xs, [Fast(0.); 5]);
+        let mut xs = [1e-20f64, 2e-20, 3e-20].map(Fast);
+        standardize_in_place(&mut xs);
+        assert!((xs[2].get() - 1.5f64.sqrt()).abs() < 1e-12);
+        // 0.0625 <= epsilon · 1e6
+        let mut xs = [1e6f32, 1e6 + 0.125].map(Fast);
+        assert_eq!(standardize_in_place(&mut xs), Some((Fast(1e6 + 0.0625), Fast(0.0625))));
+        assert_eq!(xs, [Fast(-0.0625), Fast(0.0625)]);
+    }
+
+    #[test]
+    fn single_pass_summary() {
+        let xs = [2., 4., 4., 4., 5., 5., 7., 9., -1.].map(Fast);
+        let s = summary(&xs[..8]).unwrap();
+        assert_eq!((s.min, s.max, s.count), (Fast(2.), Fast(9.), 8));
+        assert_eq!((s.mean(), s.variance()), (Fast(5.), Fast(4.)));
+        let s = summary(&xs).unwrap();
+        assert_eq!((s.sum, s.sum_sq, s.min), (Fast(39.), Fast(233.), Fast(-1.)));
+        assert_eq!(summary::<Fast<f32>>(&[]), None);
+    }
+}
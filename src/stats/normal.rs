@@ -0,0 +1,143 @@
+//! The standard normal distribution.
+//!
+//! For a normal distribution with mean `mu` and standard deviation `sigma`,
+//! use `pdf((x - mu) / sigma) / sigma`, `cdf((x - mu) / sigma)` and
+//! `mu + sigma * quantile(p)`.
+
+use FastFloat;
+use poly::eval_horner;
+
+/// `1 / sqrt(2 pi)`
+const FRAC_1_SQRT_2PI: f64 = 0.398_942_280_401_432_7;
+
+// Hart's rational approximation of the tail probability for |x| < 5 sqrt(2),
+// in the form popularized by West (2005); lowest degree first
+const HART_NUM: [f64; 7] = [
+    220.206867912376, 221.213596169931, 112.079291497871, 33.912866078383,
+    6.37396220353165, 0.700383064443688, 3.52624965998911e-2,
+];
+const HART_DEN: [f64; 8] = [
+    440.413735824752, 793.826512519948, 637.333633378831, 296.564248779674,
+    86.7807322029461, 16.064177579207, 1.75566716318264, 8.83883476483184e-2,
+];
+
+// Acklam's rational approximations of the quantile, central region and
+// tails; lowest degree first
+const ACKLAM_A: [f64; 6] = [
+    2.506628277459239, -3.066479806614716e1, 1.38357751867269e2,
+    -2.759285104469687e2, 2.209460984245205e2, -3.969683028665376e1,
+];
+const ACKLAM_B: [f64; 6] = [
+    1., -1.328068155288572e1, 6.680131188771972e1, -1.556989798598866e2,
+    1.615858368580409e2, -5.447609879822406e1,
+];
+const ACKLAM_C: [f64; 6] = [
+    2.938163982698783, 4.374664141464968, -2.549732539343734,
+    -2.400758277161838, -3.223964580411365e-1, -7.784894002430293e-3,
+];
+const ACKLAM_D: [f64; 5] = [
+    1., 3.754408661907416, 2.445134137142996, 3.224671290700398e-1,
+    7.784695709041462e-3,
+];
+const ACKLAM_LOW: f64 = 0.02425;
+
+#[inline(always)]
+fn horner<F: FastFloat, const N: usize>(coeffs: [f64; N], x: F) -> F {
+    eval_horner(&coeffs.map(F::from_f64), x)
+}
+
+/// Return the probability density of the standard normal distribution at
+/// `x`.
+#[inline]
+pub fn pdf<F: FastFloat>(x: F) -> F {
+    F::from_f64(FRAC_1_SQRT_2PI) * (x * x * F::from_f64(-0.5)).exp()
+}
+
+/// Return the cumulative distribution function of the standard normal
+/// distribution at `x`, the probability of a value less than or equal to
+/// `x`.
+///
+/// The absolute error is within a few units in the last place of `f64`.
+///
+/// ```
+/// use fast_floats::Fast;
+/// use fast_floats::stats::normal::cdf;
+///
+/// assert!((cdf(Fast(1.96f64)).get() - 0.975).abs() < 1e-4);
+/// ```
+#[inline]
+pub fn cdf<F: FastFloat>(x: F) -> F {
+    let a = x.abs();
+    let e = (a * a * F::from_f64(-0.5)).exp();
+    let tail = if a < F::from_f64(7.071_067_811_865_47) {
+        e * horner(HART_NUM, a) / horner(HART_DEN, a)
+    } else {
+        // continued fraction
+        let mut b = a + F::from_f64(0.65);
+        for k in [4., 3., 2., 1.] {
+            b = a + F::from_f64(k) / b;
+        }
+        e * F::from_f64(FRAC_1_SQRT_2PI) / b
+    };
+    if x > F::zero() { F::one() - tail } else { tail }
+}
+
+/// Return the quantile function (inverse of the cumulative distribution
+/// function) of the standard normal distribution at probability `p`.
+///
+/// Return negative infinity for `p <= 0`, positive infinity for `p >= 1`,
+/// and NaN for NaN. Acklam's approximation is refined with one step of
+/// Halley's method, for a relative error of about `1e-10` or better.
+///
+/// ```
+/// use fast_floats::Fast;
+/// use fast_floats::stats::normal::quantile;
+///
+/// assert!((quantile(Fast(0.975f64)).get() - 1.959964).abs() < 1e-6);
+/// ```
+#[inline]
+pub fn quantile<F: FastFloat>(p: F) -> F {
+    if p.is_nan() {
+        return p;
+    } else if p <= F::zero() {
+        return F::from_f64(f64::NEG_INFINITY);
+    } else if p >= F::one() {
+        return F::from_f64(f64::INFINITY);
+    }
+    let low = F::from_f64(ACKLAM_LOW);
+    let x = if p < low || p > F::one() - low {
+        let q = if p < low { p } else { F::one() - p };
+        let t = (F::from_f64(-2.) * q.ln()).sqrt();
+        let x = horner(ACKLAM_C, t) / horner(ACKLAM_D, t);
+        if p < low { x } else { -x }
+    } else {
+        let t = p - F::from_f64(0.5);
+        let r = t * t;
+        t * horner(ACKLAM_A, r) / horner(ACKLAM_B, r)
+    };
+    let u = (cdf(x) - p) / pdf(x);
+    x - u / (F::one() + F::from_f64(0.5) * x * u)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use Fast;
+
+    #[test]
+    fn pdf_cdf_quantile() {
+        assert!((pdf(Fast(0f64)).get() - FRAC_1_SQRT_2PI).abs() < 1e-15);
+        assert!((cdf(Fast(1f64)).get() - 0.841_344_746_068_542_9).abs() < 1e-15);
+        assert!((cdf(Fast(-2f64)).get() - 0.022_750_131_948_179_2).abs() < 1e-15);
+        assert!((cdf(Fast(-8f64)).get() / 6.220_960_574_271_78e-16 - 1.).abs() < 1e-7);
+        assert!((cdf(Fast(0.5f32)).get() - 0.691_462_5).abs() < 1e-6);
+
+        for &p in &[1e-12f64, 0.001, 0.1, 0.5, 0.9, 0.99999] {
+            let x = quantile(Fast(p));
+            assert!((cdf(x).get() / p - 1.).abs() < 1e-9);
+        }
+        assert!((quantile(Fast(0.975f32)).get() - 1.959_964).abs() < 1e-5);
+        assert_eq!(quantile(Fast(0f64)).get(), f64::NEG_INFINITY);
+        assert!(quantile(Fast(f32::NAN)).get().is_nan());
+    }
+}
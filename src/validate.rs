@@ -0,0 +1,131 @@
+//! Screening of slices for NaN and infinities.
+//!
+//! The fast-math operations assume that their inputs and results are
+//! finite, and may produce arbitrary results otherwise; these scanners check
+//! buffers before handing them to fast kernels. They test the bit patterns
+//! of the floats, since the compiler is allowed to fold away `is_nan` checks
+//! on `Fast` values, and they test whole chunks at a time without early
+//! exits, so that the scanning loop is vectorized.
+
+use Fast;
+
+/// Number of elements tested together between early exits.
+const CHUNK: usize = 16;
+
+mod private {
+    pub trait Sealed {}
+}
+
+/// Floats that can be scanned: `f32`, `f64`, `Fast<f32>` and `Fast<f64>`.
+///
+/// The trait is sealed and can not be implemented outside of this crate.
+pub trait Scan: private::Sealed + Copy {
+    #[doc(hidden)]
+    fn is_nan_bits(self) -> bool;
+    #[doc(hidden)]
+    fn is_non_finite_bits(self) -> bool;
+}
+
+macro_rules! impl_scan {
+    ($($f:ident $exp_mask:expr, $abs_mask:expr;)+) => {
+        $(
+        impl private::Sealed for $f {}
+        impl private::Sealed for Fast<$f> {}
+
+        impl Scan for $f {
+            #[inline(always)]
+            fn is_nan_bits(self) -> bool {
+                self.to_bits() & $abs_mask > $exp_mask
+            }
+
+            #[inline(always)]
+            fn is_non_finite_bits(self) -> bool {
+                self.to_bits() & $exp_mask == $exp_mask
+            }
+        }
+
+        impl Scan for Fast<$f> {
+            #[inline(always)]
+            fn is_nan_bits(self) -> bool {
+                self.0.is_nan_bits()
+            }
+
+            #[inline(always)]
+            fn is_non_finite_bits(self) -> bool {
+                self.0.is_non_finite_bits()
+            }
+        }
+        )+
+    }
+}
+
+impl_scan! {
+    f32 0x7f80_0000, 0x7fff_ffff;
+    f64 0x7ff0_0000_0000_0000, 0x7fff_ffff_ffff_ffff;
+}
+
+#[inline(always)]
+fn any_in_chunk<T: Scan>(xs: &[T], test: fn(T) -> bool) -> bool {
+    xs.iter().fold(false, |acc, &x| acc | test(x))
+}
+
+#[inline]
+fn find_first<T: Scan>(xs: &[T], test: fn(T) -> bool) -> Option<usize> {
+    xs.chunks(CHUNK).enumerate()
+        .find(|&(_, c)| any_in_chunk(c, test))
+        .and_then(|(i, c)| c.iter().position(|&x| test(x)).map(|j| i * CHUNK + j))
+}
+
+/// Return `true` if any element of `xs` is NaN.
+///
+/// ```
+/// use fast_floats::validate::has_nan;
+///
+/// assert!(has_nan(&[1., f64::NAN, 3.]));
+/// assert!(!has_nan(&[1., f64::INFINITY]));
+/// ```
+pub fn has_nan<T: Scan>(xs: &[T]) -> bool {
+    find_first(xs, T::is_nan_bits).is_some()
+}
+
+/// Return `true` if any element of `xs` is NaN or infinite.
+pub fn has_non_finite<T: Scan>(xs: &[T]) -> bool {
+    find_first_non_finite(xs).is_some()
+}
+
+/// Return the index of the first element of `xs` that is NaN or infinite,
+/// or `None` if all are finite.
+///
+/// ```
+/// use fast_floats::Fast;
+/// use fast_floats::validate::find_first_non_finite;
+///
+/// let xs = [0., 1., f32::NEG_INFINITY, f32::NAN].map(Fast);
+/// assert_eq!(find_first_non_finite(&xs), Some(2));
+/// ```
+pub fn find_first_non_finite<T: Scan>(xs: &[T]) -> Option<usize> {
+    find_first(xs, T::is_non_finite_bits)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn scan() {
+        let mut xs = [1f64; 40];
+        assert!(!has_nan(&xs) && !has_non_finite(&xs));
+        assert_eq!(find_first_non_finite(&xs), None);
+        xs[37] = -f64::NAN;
+        xs[39] = f64::INFINITY;
+        assert!(has_nan(&xs));
+        assert_eq!(find_first_non_finite(&xs), Some(37));
+        xs[37] = f64::MAX;
+        assert!(!has_nan(&xs) && has_non_finite(&xs));
+        assert_eq!(find_first_non_finite(&xs), Some(39));
+
+        let ys = [Fast(f32::MIN_POSITIVE / 2.), Fast(-0.), Fast(f32::NAN)];
+        assert_eq!(find_first_non_finite(&ys), Some(2));
+        assert!(!has_nan(&ys[..2]) && !has_nan::<f32>(&[]));
+    }
+}
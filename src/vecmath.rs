@@ -0,0 +1,322 @@
+//! Inlined implementations of `exp`, `ln`, `sin`, `cos` and `powf`.
+//!
+//! With the `vecmath` feature, these replace the intrinsics, which are
+//! usually lowered to calls to the system math library and so prevent the
+//! compiler from vectorizing loops that use them. The implementations here
+//! are straight-line code (range reduction, a polynomial and bit
+//! manipulation), which the compiler can inline and vectorize.
+//!
+//! The polynomials are truncated Taylor series, evaluated with Estrin's
+//! scheme; the error is a few units in the last place within the ranges
+//! documented on each method. As everywhere in this crate, infinities and
+//! NaN are not expected as inputs.
+
+use std::intrinsics;
+
+use Fast;
+use poly::eval_estrin;
+
+/// Coefficients of `exp(r)`: `1 / k!`
+const fn exp_coeffs<const N: usize>() -> [f64; N] {
+    let mut c = [1.; N];
+    let mut k = 1;
+    while k < N {
+        c[k] = c[k - 1] / k as f64;
+        k += 1;
+    }
+    c
+}
+
+/// Coefficients of `ln((1 + s) / (1 - s)) / s` in `s²`: `2 / (2k + 1)`
+const fn atanh_coeffs<const N: usize>() -> [f64; N] {
+    let mut c = [0.; N];
+    let mut k = 0;
+    while k < N {
+        c[k] = 2. / (2 * k + 1) as f64;
+        k += 1;
+    }
+    c
+}
+
+/// Coefficients of `sin(r) / r` in `r²`: `(-1)^k / (2k + 1)!`
+const fn sin_coeffs<const N: usize>() -> [f64; N] {
+    let mut c = [1.; N];
+    let mut k = 1;
+    while k < N {
+        c[k] = -c[k - 1] / (2 * k * (2 * k + 1)) as f64;
+        k += 1;
+    }
+    c
+}
+
+/// Coefficients of `cos(r)` in `r²`: `(-1)^k / (2k)!`
+const fn cos_coeffs<const N: usize>() -> [f64; N] {
+    let mut c = [1.; N];
+    let mut k = 1;
+    while k < N {
+        c[k] = -c[k - 1] / ((2 * k - 1) * 2 * k) as f64;
+        k += 1;
+    }
+    c
+}
+
+macro_rules! impl_vecmath {
+    ($($m:ident: $f:ident, $bits:ident {
+        fmuladd: $fmuladd:ident,
+        powf: $powf:ident,
+        mantissa: $mantissa:expr,
+        bias: $bias:expr,
+        exp_terms: $exp_terms:expr,
+        atanh_terms: $atanh_terms:expr,
+        sin_terms: $sin_terms:expr,
+        cos_terms: $cos_terms:expr,
+        exp_range: ($exp_lo:expr, $exp_hi:expr),
+        ln_2: ($ln2_hi:expr, $ln2_lo:expr),
+        frac_pi_2: ($pio2_1:expr, $pio2_2:expr, $pio2_3:expr),
+    })+) => {
+        $(
+        mod $m {
+            use super::*;
+            use std::$f::consts::{LOG2_E, FRAC_2_PI, SQRT_2};
+
+            const fn fast_coeffs<const N: usize>(c: [f64; N]) -> [Fast<$f>; N] {
+                let mut out = [Fast(0.); N];
+                let mut k = 0;
+                while k < N {
+                    out[k] = Fast(c[k] as $f);
+                    k += 1;
+                }
+                out
+            }
+
+            const EXP: [Fast<$f>; $exp_terms] = fast_coeffs(exp_coeffs());
+            const ATANH: [Fast<$f>; $atanh_terms] = fast_coeffs(atanh_coeffs());
+            const SIN: [Fast<$f>; $sin_terms] = fast_coeffs(sin_coeffs());
+            const COS: [Fast<$f>; $cos_terms] = fast_coeffs(cos_coeffs());
+
+            /// Adding and subtracting this rounds to an integer, for
+            /// magnitudes below half of it.
+            const ROUND: $f = 1.5 * (1u64 << $mantissa) as $f;
+
+            /// Round `x` to the nearest integer, ties to even.
+            ///
+            /// This uses precise arithmetic, since fast-math would fold the
+            /// addition and subtraction, and the `round` intrinsic isn't
+            /// vectorized without SSE4.1 on x86.
+            #[inline(always)]
+            fn round(x: Fast<$f>) -> Fast<$f> {
+                Fast((x.0 + ROUND) - ROUND)
+            }
+
+            /// Return `a · b + c`, fused if the target has FMA instructions.
+            ///
+            /// The `fma` intrinsic is a call to the math library on targets
+            /// without them, which would stop loops from being vectorized.
+            #[allow(unused_unsafe)]
+            #[inline(always)]
+            fn mul_add(a: Fast<$f>, b: Fast<$f>, c: Fast<$f>) -> Fast<$f> {
+                Fast(unsafe { intrinsics::$fmuladd(a.0, b.0, c.0) })
+            }
+
+            /// Return `2^n`, for `n` in the range of normal exponents.
+            #[inline(always)]
+            fn exp2i(n: Fast<$f>) -> Fast<$f> {
+                Fast($f::from_bits(((n.0 as i32 + $bias) as $bits) << $mantissa))
+            }
+
+            /// Return `sin(x)` for `quadrant` 0, and the sine shifted by
+            /// `quadrant` quarter turns otherwise.
+            #[inline(always)]
+            fn sin_quadrant(x: Fast<$f>, quadrant: i32) -> Fast<$f> {
+                let n = round(x * Fast(FRAC_2_PI));
+                let mut r = mul_add(-n, Fast($pio2_1), x);
+                r = mul_add(-n, Fast($pio2_2), r);
+                r = mul_add(-n, Fast($pio2_3), r);
+                let r2 = r * r;
+                let q = (n.0 as i32).wrapping_add(quadrant);
+                let y = if q & 1 == 0 {
+                    r * eval_estrin(&SIN, r2)
+                } else {
+                    eval_estrin(&COS, r2)
+                };
+                if q & 2 == 0 { y } else { -y }
+            }
+
+            impl Fast<$f> {
+                /// Return `e^self`.
+                ///
+                /// Results below the smallest normal float are zero.
+                #[inline(always)]
+                pub fn exp(self) -> Self {
+                    let x = self.max(Fast($exp_lo)).min(Fast($exp_hi));
+                    // n = floor(x / ln 2), up to ties, so that r is in [0, ln 2]
+                    let n = round(mul_add(x, Fast(LOG2_E), Fast(-0.5)));
+                    let r = mul_add(-n, Fast($ln2_hi), x);
+                    let r = mul_add(-n, Fast($ln2_lo), r);
+                    let y = eval_estrin(&EXP, r) * exp2i(n);
+                    if self < Fast($exp_lo) {
+                        Fast(0.)
+                    } else if self > Fast($exp_hi) {
+                        Fast($f::INFINITY)
+                    } else {
+                        y
+                    }
+                }
+
+                /// Return the natural logarithm of `self`.
+                ///
+                /// Return negative infinity for zero and NaN for negative
+                /// numbers.
+                #[inline(always)]
+                pub fn ln(self) -> Self {
+                    // scale subnormals to normals
+                    let tiny = self < Fast($f::MIN_POSITIVE);
+                    let x = if tiny { self * exp2i(Fast($mantissa as $f)) } else { self };
+                    let bits = x.0.to_bits();
+                    let e = (bits >> $mantissa) as i32 - $bias;
+                    // m in [1, 2), then [sqrt(1/2), sqrt(2))
+                    let m = Fast($f::from_bits(bits & ((1 << $mantissa) - 1)
+                                               | (($bias as $bits) << $mantissa)));
+                    let big = m > Fast(SQRT_2);
+                    let m = if big { m * Fast(0.5) } else { m };
+                    let e = e + big as i32 - if tiny { $mantissa } else { 0 };
+                    let e = Fast(e as $f);
+                    // ln(m) = 2 atanh(s)
+                    let s = (m - Fast(1.)) / (m + Fast(1.));
+                    let y = s * eval_estrin(&ATANH, s * s);
+                    let y = mul_add(e, Fast($ln2_hi), mul_add(e, Fast($ln2_lo), y));
+                    if self > Fast(0.) {
+                        y
+                    } else if self == Fast(0.) {
+                        Fast($f::NEG_INFINITY)
+                    } else {
+                        Fast($f::NAN)
+                    }
+                }
+
+                /// Return the sine of `self` (in radians).
+                ///
+                /// Accurate for magnitudes up to about `1e5`.
+                #[inline(always)]
+                pub fn sin(self) -> Self {
+                    sin_quadrant(self, 0)
+                }
+
+                /// Return the cosine of `self` (in radians).
+                ///
+                /// Accurate for magnitudes up to about `1e5`.
+                #[inline(always)]
+                pub fn cos(self) -> Self {
+                    sin_quadrant(self, 1)
+                }
+
+                /// Return `self` raised to the power `n`, as `e^(n ln self)`.
+                ///
+                /// The relative error grows with the magnitude of
+                /// `n ln self`. A zero base gives zero for positive `n`;
+                /// other zero and negative bases fall back to the
+                /// intrinsic, so loops over them are not vectorized.
+                #[allow(unused_unsafe)]
+                #[inline(always)]
+                pub fn powf(self, n: Self) -> Self {
+                    // ln 0 is infinite, which fast-math must not see
+                    if self == Fast(0.) && n > Fast(0.) {
+                        return Fast(0.);
+                    }
+                    if self <= Fast(0.) {
+                        return Fast(unsafe { intrinsics::$powf(self.0, n.0) });
+                    }
+                    (n * self.ln()).exp()
+                }
+            }
+        }
+        )+
+    }
+}
+
+impl_vecmath! {
+    single: f32, u32 {
+        fmuladd: fmuladdf32,
+        powf: powf32,
+        mantissa: 23,
+        bias: 127,
+        exp_terms: 10,
+        atanh_terms: 5,
+        sin_terms: 5,
+        cos_terms: 5,
+        exp_range: (-87.33, 88.72),
+        ln_2: (0.693_359_4, -2.121_944_4e-4),
+        frac_pi_2: (1.570_312_5, 4.837_513e-4, 7.549_79e-8),
+    }
+    double: f64, u64 {
+        fmuladd: fmuladdf64,
+        powf: powf64,
+        mantissa: 52,
+        bias: 1023,
+        exp_terms: 17,
+        atanh_terms: 11,
+        sin_terms: 9,
+        cos_terms: 9,
+        exp_range: (-708.39, 709.78),
+        ln_2: (6.931_471_803_691_238e-1, 1.908_214_929_270_587_7e-10),
+        frac_pi_2: (1.570_796_326_734_125_6, 6.077_100_506_303_966e-11,
+                    2.022_266_248_795_950_6e-21),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::intrinsics;
+    use Fast;
+
+    fn check64(x: f64, got: Fast<f64>, want: f64, ulps: f64) {
+        let err = (got.get() - want).abs() / (want.abs() * f64::EPSILON);
+        assert!(err <= ulps, "x = {}: {} != {} ({} ulps)", x, got.get(), want, err);
+    }
+
+    fn check32(x: f32, got: Fast<f32>, want: f32, ulps: f32) {
+        let err = (got.get() - want).abs() / (want.abs() * f32::EPSILON);
+        assert!(err <= ulps, "x = {}: {} != {} ({} ulps)", x, got.get(), want, err);
+    }
+
+    // the intrinsics are unsafe in older nightlies
+    #[allow(unused_unsafe)]
+    #[test]
+    fn against_intrinsics() {
+        let mut x = -700f64;
+        while x < 700. {
+            check64(x, Fast(x).exp(), unsafe { intrinsics::expf64(x) }, 4.);
+            let y = (x / 8.1) as f32;
+            check32(y, Fast(y).exp(), unsafe { intrinsics::expf32(y) }, 4.);
+            x += 0.0913;
+        }
+        let mut x = 1e-310f64;
+        while x < 1e300 {
+            check64(x, Fast(x).ln(), unsafe { intrinsics::logf64(x) }, 4.);
+            let y = x as f32;
+            if y > 0. && y.is_finite() && (y - 1.).abs() > 1e-3 {
+                check32(y, Fast(y).ln(), unsafe { intrinsics::logf32(y) }, 4.);
+            }
+            x *= 1.37;
+        }
+        // absolute error near zeros
+        let mut x = -1000f64;
+        while x < 1000. {
+            let (s, c) = unsafe { (intrinsics::sinf64(x), intrinsics::cosf64(x)) };
+            assert!((Fast(x).sin().get() - s).abs() < 4. * f64::EPSILON);
+            assert!((Fast(x).cos().get() - c).abs() < 4. * f64::EPSILON);
+            let y = x as f32;
+            let (s, c) = unsafe { (intrinsics::sinf32(y), intrinsics::cosf32(y)) };
+            assert!((Fast(y).sin().get() - s).abs() < 4. * f32::EPSILON);
+            assert!((Fast(y).cos().get() - c).abs() < 4. * f32::EPSILON);
+            x += 0.0317;
+        }
+        check64(2., Fast(2f64).powf(Fast(0.5)), 2f64.sqrt(), 4.);
+        assert_eq!(Fast(-2f64).powf(Fast(2.)), Fast(4.));
+        assert_eq!(Fast(-2f32).powf(Fast(3.)), Fast(-8.));
+        assert_eq!(Fast(0f64).powf(Fast(2.2)), Fast(0.));
+        assert_eq!(Fast(0f32).powf(Fast(0.)), Fast(1.));
+        assert_eq!(Fast(-800f64).exp(), Fast(0.));
+        assert_eq!(Fast(0f32).ln(), Fast(f32::NEG_INFINITY));
+    }
+}